@@ -0,0 +1,344 @@
+use std::fmt::Write;
+
+use crate::style::{
+    AnsiColor, Blink, Color, Delete, FontStyle, Hide, Intensity, Invert, Rgb, Style, Underline,
+};
+#[cfg(feature = "uncommon")]
+use crate::style::{Frame, Overline};
+
+use super::{Segment, Segments, apply_sgr};
+
+/// A style attribute that is written as its own tag.
+#[derive(Clone, Copy, PartialEq)]
+enum Attr {
+    Bold,
+    Dim,
+    FontStyle(FontStyle),
+    Underline(Underline),
+    UlColor(Color),
+    Blink(Blink),
+    Invert,
+    Hide,
+    Delete,
+    #[cfg(feature = "uncommon")]
+    Overline,
+    #[cfg(feature = "uncommon")]
+    Frame(Frame),
+    Fg(Color),
+    Bg(Color),
+}
+
+impl Attr {
+    fn open(self, out: &mut String) {
+        match self {
+            Attr::Bold => out.push_str("<b>"),
+            Attr::Dim => out.push_str("<d>"),
+            Attr::FontStyle(FontStyle::Fraktur) => out.push_str("<i fraktur>"),
+            Attr::FontStyle(_) => out.push_str("<i>"),
+            Attr::Underline(underline) => out.push_str(match underline {
+                Underline::Double => "<u double>",
+                Underline::Curly => "<u curly>",
+                Underline::Dotted => "<u dotted>",
+                Underline::Dashed => "<u dashed>",
+                _ => "<u>",
+            }),
+            Attr::UlColor(color) => {
+                out.push_str("<span ul=\"");
+                write_color_value(color, out);
+                out.push_str("\">");
+            }
+            Attr::Blink(Blink::Fast) => out.push_str("<k fast>"),
+            Attr::Blink(_) => out.push_str("<k>"),
+            Attr::Invert => out.push_str("<r>"),
+            Attr::Hide => out.push_str("<h>"),
+            Attr::Delete => out.push_str("<s>"),
+            #[cfg(feature = "uncommon")]
+            Attr::Overline => out.push_str("<span o>"),
+            #[cfg(feature = "uncommon")]
+            Attr::Frame(Frame::Encircled) => out.push_str("<span encircled>"),
+            #[cfg(feature = "uncommon")]
+            Attr::Frame(_) => out.push_str("<span framed>"),
+            Attr::Fg(color) => {
+                out.push_str("<c ");
+                write_color(color, out);
+                out.push('>');
+            }
+            Attr::Bg(color) => {
+                out.push_str("<x ");
+                write_color(color, out);
+                out.push('>');
+            }
+        }
+    }
+
+    fn close(self, out: &mut String) {
+        out.push_str(match self {
+            Attr::Bold => "</b>",
+            Attr::Dim => "</d>",
+            Attr::FontStyle(_) => "</i>",
+            Attr::Underline(_) => "</u>",
+            Attr::UlColor(_) => "</span>",
+            Attr::Blink(_) => "</k>",
+            Attr::Invert => "</r>",
+            Attr::Hide => "</h>",
+            Attr::Delete => "</s>",
+            #[cfg(feature = "uncommon")]
+            Attr::Overline | Attr::Frame(_) => "</span>",
+            Attr::Fg(_) => "</c>",
+            Attr::Bg(_) => "</x>",
+        });
+    }
+}
+
+/// Returns the attributes of a style, outermost first.
+fn attrs(style: &Style) -> Vec<Attr> {
+    let mut attrs = Vec::new();
+
+    match style.intensity() {
+        Intensity::Bold => attrs.push(Attr::Bold),
+        Intensity::Dim => attrs.push(Attr::Dim),
+        _ => {}
+    }
+    if let font_style @ (FontStyle::Italics | FontStyle::Fraktur) = style.font_style() {
+        attrs.push(Attr::FontStyle(font_style));
+    }
+    match style.underline() {
+        Underline::None | Underline::Unset => {}
+        underline => attrs.push(Attr::Underline(underline)),
+    }
+    if is_set(style.ul_color()) {
+        attrs.push(Attr::UlColor(style.ul_color()));
+    }
+    if let blink @ (Blink::Slow | Blink::Fast) = style.blink() {
+        attrs.push(Attr::Blink(blink));
+    }
+    if style.invert() == Invert::Set {
+        attrs.push(Attr::Invert);
+    }
+    if style.hide() == Hide::Set {
+        attrs.push(Attr::Hide);
+    }
+    if style.delete() == Delete::Set {
+        attrs.push(Attr::Delete);
+    }
+    #[cfg(feature = "uncommon")]
+    if style.overline() == Overline::Set {
+        attrs.push(Attr::Overline);
+    }
+    #[cfg(feature = "uncommon")]
+    if let frame @ (Frame::Framed | Frame::Encircled) = style.frame() {
+        attrs.push(Attr::Frame(frame));
+    }
+    if is_set(style.bg_color()) {
+        attrs.push(Attr::Bg(style.bg_color()));
+    }
+    if is_set(style.fg_color()) {
+        attrs.push(Attr::Fg(style.fg_color()));
+    }
+
+    attrs
+}
+
+fn is_set(color: Color) -> bool {
+    !matches!(
+        color,
+        Color::None | Color::Unset | Color::AnsiColor(AnsiColor::Default)
+    )
+}
+
+fn write_color(color: Color, out: &mut String) {
+    match color {
+        Color::AnsiColor(color) => {
+            let n = color as u8;
+            let name = [
+                "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+            ][usize::from(n % 60)];
+            out.push_str(name);
+            if n >= 60 {
+                out.push_str("=\"light\"");
+            }
+        }
+        Color::Ansi256(color) => {
+            let _ = write!(out, "fixed=\"{}\"", color.0);
+        }
+        Color::Rgb(Rgb(r, g, b)) => {
+            let _ = write!(out, "rgb=\"{r}, {g}, {b}\"");
+        }
+        Color::None | Color::Unset => {}
+    }
+}
+
+/// Writes a color in the form taken by attribute values like `ul="..."`.
+fn write_color_value(color: Color, out: &mut String) {
+    match color {
+        Color::AnsiColor(color) => {
+            let n = color as u8;
+            // light colors have no name here, so they use their 256-color index
+            let _ = write!(out, "fixed({})", if n >= 60 { n - 52 } else { n });
+        }
+        Color::Ansi256(color) => {
+            let _ = write!(out, "fixed({})", color.0);
+        }
+        Color::Rgb(Rgb(r, g, b)) => {
+            let _ = write!(out, "rgb({r}, {g}, {b})");
+        }
+        Color::None | Color::Unset => {}
+    }
+}
+
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        if matches!(c, '\\' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// Closes the open tags that are not wanted anymore and opens the missing ones.
+fn sync(open: &mut Vec<Attr>, wanted: &[Attr], out: &mut String) {
+    let keep = open
+        .iter()
+        .position(|attr| !wanted.contains(attr))
+        .unwrap_or(open.len());
+    for attr in open.drain(keep..).rev() {
+        attr.close(out);
+    }
+
+    for attr in wanted {
+        if !open.contains(attr) {
+            attr.open(out);
+            open.push(*attr);
+        }
+    }
+}
+
+/// Converts text containing ANSI escape sequences to ziyy markup.
+///
+/// Each style attribute becomes its own tag and tags are only opened or closed when the
+/// style changes, so the output nests properly. OSC 8 hyperlinks become `<a href="...">`
+/// and other escape sequences are kept as `\e` escapes.
+///
+/// # Example
+///
+/// ```
+/// # use ziyy_core as ziyy;
+/// use ziyy::ansi_to_markup;
+///
+/// let markup = ansi_to_markup("\x1b[1;31merror\x1b[0m: not found");
+/// assert_eq!(markup, "<b><c red>error</c></b>: not found");
+/// ```
+#[must_use]
+pub fn ansi_to_markup(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut style = Style::new();
+    let mut open = Vec::new();
+    let mut link = false;
+
+    for segment in Segments::new(source.as_bytes()) {
+        match segment {
+            Segment::Text(text) => {
+                // <a> drops styles of the tags nested in it.
+                if !link {
+                    sync(&mut open, &attrs(&style), &mut out);
+                }
+                escape(&String::from_utf8_lossy(text), &mut out);
+            }
+
+            Segment::Sgr(sgr) => style = apply_sgr(style, sgr),
+
+            Segment::Link(uri) => {
+                sync(&mut open, &[], &mut out);
+                if link {
+                    out.push_str("</a>");
+                }
+
+                link = !uri.is_empty();
+                if link {
+                    out.push_str("<a href=\"");
+                    out.push_str(&String::from_utf8_lossy(uri).replace('"', "%22"));
+                    out.push_str("\">");
+                }
+            }
+
+            Segment::Other(seq) => {
+                out.push_str("\\e");
+                escape(&String::from_utf8_lossy(&seq[1..]), &mut out);
+            }
+        }
+    }
+
+    sync(&mut open, &[], &mut out);
+    if link {
+        out.push_str("</a>");
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi_to_markup() {
+        assert_eq!(
+            ansi_to_markup("\x1b[1;31merror\x1b[0m: a <b>"),
+            "<b><c red>error</c></b>: a \\<b\\>"
+        );
+        assert_eq!(
+            ansi_to_markup("\x1b[1mbold \x1b[4mboth\x1b[22m under\x1b[24m"),
+            "<b>bold <u>both</u></b><u> under</u>"
+        );
+        assert_eq!(
+            ansi_to_markup("\x1b[38;2;1;2;3;48;5;200;91ma"),
+            "<x fixed=\"200\"><c red=\"light\">a</c></x>"
+        );
+        assert_eq!(
+            ansi_to_markup("\x1b[4:3mcurly\x1b[21m double\x1b[24m"),
+            "<u curly>curly</u><u double> double</u>"
+        );
+        assert_eq!(
+            ansi_to_markup("\x1b[4;58;2;1;2;3ma\x1b[58;5;9mb\x1b[59mc\x1b[24m"),
+            "<u><span ul=\"rgb(1, 2, 3)\">a</span><span ul=\"fixed(9)\">b</span>c</u>"
+        );
+        assert_eq!(
+            ansi_to_markup("\x1b[6;20mfast\x1b[25;23m"),
+            "<i fraktur><k fast>fast</k></i>"
+        );
+        assert_eq!(
+            ansi_to_markup("\x1b]8;;https://x.y\x1b\\link\x1b]8;;\x1b\\\x1b[2K"),
+            "<a href=\"https://x.y\">link</a>\\e[2K"
+        );
+    }
+
+    #[test]
+    fn test_ansi_to_markup_round_trip() {
+        let source = "\x1b[32m\x1b[1mok\x1b[39m \x1b[3m\x1b[38;5;4mdone\x1b[0m!";
+        assert_eq!(
+            crate::style(&ansi_to_markup(source)),
+            "\x1b[1m\x1b[32mok\x1b[39m \x1b[3m\x1b[38;5;4mdone\x1b[39m\x1b[23m\x1b[22m!"
+        );
+
+        let source = "\x1b[4;58;5;9mred\x1b[59m plain\x1b[24m";
+        assert_eq!(
+            crate::style(&ansi_to_markup(source)),
+            "\x1b[4m\x1b[58;5;9mred\x1b[59m plain\x1b[24m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uncommon")]
+    fn test_ansi_to_markup_uncommon_styles() {
+        assert_eq!(
+            ansi_to_markup("\x1b[53;51ma\x1b[52mb\x1b[54;55mc"),
+            "<span o><span framed>a</span><span encircled>b</span></span>c"
+        );
+
+        let source = "\x1b[53mo\x1b[52mboth\x1b[54;55m!";
+        assert_eq!(
+            crate::style(&ansi_to_markup(source)),
+            "\x1b[53mo\x1b[52mboth\x1b[54m\x1b[55m!"
+        );
+    }
+}
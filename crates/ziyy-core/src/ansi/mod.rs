@@ -0,0 +1,125 @@
+use crate::parser::Tag;
+use crate::shared::Span;
+use crate::style::{Reset, Style};
+
+pub use markup::ansi_to_markup;
+
+mod markup;
+
+/// A piece of text containing ANSI escape sequences.
+pub(crate) enum Segment<'a> {
+    /// Text without escape sequences.
+    Text(&'a [u8]),
+    /// A Select Graphic Rendition sequence.
+    Sgr(Style),
+    /// An OSC 8 hyperlink. An empty uri ends the current link.
+    Link(&'a [u8]),
+    /// Any other escape sequence.
+    Other(&'a [u8]),
+}
+
+/// An iterator over the [`Segment`]s of a text.
+pub(crate) struct Segments<'a> {
+    source: &'a [u8],
+    current: usize,
+}
+
+impl<'a> Segments<'a> {
+    pub(crate) fn new(source: &'a [u8]) -> Self {
+        Segments { source, current: 0 }
+    }
+
+    /// Returns the offset in the source after the last returned segment.
+    pub(crate) fn offset(&self) -> usize {
+        self.current
+    }
+
+    /// Scans a Control Sequence Introducer sequence (`ESC [ ... final`).
+    fn csi(&mut self, start: usize) -> Segment<'a> {
+        let params = start + 2;
+        let Some(n) = self.source[params..]
+            .iter()
+            .position(|b| matches!(b, 0x40..=0x7e))
+        else {
+            self.current = self.source.len();
+            return Segment::Other(&self.source[start..]);
+        };
+        let end = params + n;
+        self.current = end + 1;
+
+        if self.source[end] == b'm' {
+            let tag = Tag::parse_from_ansi(&self.source[params..end], Span::inserted());
+            Segment::Sgr(tag.style)
+        } else {
+            Segment::Other(&self.source[start..self.current])
+        }
+    }
+
+    /// Scans an Operating System Command sequence (`ESC ] ... ST`).
+    fn osc(&mut self, start: usize) -> Segment<'a> {
+        let params = start + 2;
+        let mut end = params;
+        self.current = self.source.len();
+        while end < self.source.len() {
+            if self.source[end] == 0x07 {
+                self.current = end + 1;
+                break;
+            }
+            if self.source[end] == 0x1b && self.source.get(end + 1) == Some(&b'\\') {
+                self.current = end + 2;
+                break;
+            }
+            end += 1;
+        }
+
+        match self.source[params..end].strip_prefix(b"8;") {
+            Some(params) => match params.iter().position(|b| *b == b';') {
+                Some(n) => Segment::Link(&params[n + 1..]),
+                None => Segment::Link(b""),
+            },
+            None => Segment::Other(&self.source[start..self.current]),
+        }
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.current;
+        if start >= self.source.len() {
+            return None;
+        }
+
+        if self.source[start] == 0x1b {
+            return Some(match self.source.get(start + 1) {
+                Some(b'[') => self.csi(start),
+                Some(b']') => self.osc(start),
+                Some(b) if b.is_ascii() => {
+                    self.current += 2;
+                    Segment::Other(&self.source[start..self.current])
+                }
+                _ => {
+                    self.current += 1;
+                    Segment::Other(&self.source[start..self.current])
+                }
+            });
+        }
+
+        self.current = match self.source[start..].iter().position(|b| *b == 0x1b) {
+            Some(n) => start + n,
+            None => self.source.len(),
+        };
+        Some(Segment::Text(&self.source[start..self.current]))
+    }
+}
+
+/// Applies the style of a Select Graphic Rendition sequence to the current style.
+pub(crate) fn apply_sgr(style: Style, mut sgr: Style) -> Style {
+    if sgr.reset().is_set() {
+        sgr.set_reset(Reset::No);
+        Style::new() + sgr
+    } else {
+        style + sgr
+    }
+}
@@ -15,16 +15,30 @@ pub static BUILTIN_STYLES: LazyLock<HashMap<&[u8], Style>> = LazyLock::new(|| {
         (b"i", Tag::new(TagName::<str>::I, TagKind::Open).style),
         (b"s", Tag::new(TagName::<str>::S, TagKind::Open).style),
         (b"u", Tag::new(TagName::<str>::U, TagKind::Open).style),
+        // defaults of the headings, replaced by bindings of the same name
+        (
+            b"h1",
+            Tag::new(TagName::<str>::B, TagKind::Open).style
+                + Tag::new(TagName::<str>::U, TagKind::Open).style,
+        ),
+        (b"h2", Tag::new(TagName::<str>::B, TagKind::Open).style),
+        (b"h3", Tag::new(TagName::<str>::I, TagKind::Open).style),
     ]
     .into()
 });
 
+/// Checks if `input` is the name of a builtin tag, which `<let>` can not declare. Headings
+/// are not builtin, their defaults are replaced by bindings of the same name.
 #[inline]
 pub fn is_builtin_tag<I: ?Sized + Input>(input: &I) -> bool {
     let mut scanner = Scanner::new(input);
+    scanner.text_mode = false;
     if let Some(token) = scanner.scan_one() {
         if let Ok(name) = match_tag_name(&token) {
-            return !matches!(name, TagName::Any(_));
+            return !matches!(
+                name,
+                TagName::Any(_) | TagName::H1 | TagName::H2 | TagName::H3
+            );
         }
     }
 
@@ -1,20 +1,26 @@
 use std::collections::HashMap;
+#[cfg(feature = "bindings")]
+use std::sync::Arc;
 #[cfg(feature = "terminfo")]
 use std::sync::LazyLock;
 
-use crate::parser::Chunk;
+use crate::parser::{Chunk, Tag};
 use crate::scanner::Scanner;
 use crate::shared::Input;
 use crate::style::Style;
+#[cfg(feature = "bindings")]
+use crate::stylesheet::Stylesheet;
 
 use state::State;
+pub(crate) use state::revert;
 #[cfg(feature = "terminfo")]
 use terminfo::Database;
 
 mod state;
 
 #[cfg(feature = "terminfo")]
-static DATABASE: LazyLock<Option<Database>> = LazyLock::new(|| Database::from_env().ok());
+pub(crate) static DATABASE: LazyLock<Option<Database>> =
+    LazyLock::new(|| Database::from_env().ok());
 
 pub struct Context<'src, I: ?Sized + Input> {
     /// The scanner used to tokenize the input source.
@@ -22,10 +28,15 @@ pub struct Context<'src, I: ?Sized + Input> {
     /// Optional bindings for styles.
     #[cfg(feature = "bindings")]
     pub(crate) bindings: Option<HashMap<&'src [u8], Style>>,
+    /// Bindings shared by every input, below the bindings of the input.
+    #[cfg(feature = "bindings")]
+    pub(crate) stylesheet: Option<Arc<Stylesheet>>,
     /// The current state of the parser.
     pub(crate) state: State<'src, I>,
     /// The next chunk to be parsed.
     pub(crate) next_chunk: Option<Chunk<'src, I>>,
+    /// The last tag that failed to parse, kept to recover from the error.
+    pub(crate) malformed_tag: Option<Tag<'src, I>>,
 }
 
 impl<'src, I: ?Sized + Input> Context<'src, I> {
@@ -35,8 +46,10 @@ impl<'src, I: ?Sized + Input> Context<'src, I> {
         Self {
             scanner: Scanner::new(input),
             bindings,
+            stylesheet: None,
             state: State::new(),
             next_chunk: None,
+            malformed_tag: None,
         }
     }
 
@@ -47,6 +60,22 @@ impl<'src, I: ?Sized + Input> Context<'src, I> {
             scanner: Scanner::new(input),
             state: State::new(),
             next_chunk: None,
+            malformed_tag: None,
         }
     }
+
+    /// Returns the style bound to `name` by a `<let/>` of the input or by the stylesheet.
+    #[cfg(feature = "bindings")]
+    pub(crate) fn binding(&self, name: &[u8]) -> Option<&Style> {
+        self.bindings
+            .as_ref()
+            .and_then(|bindings| bindings.get(name))
+            .or_else(|| self.stylesheet.as_ref()?.binding(name))
+    }
+
+    /// Returns `true` if custom tags can be bound to a style.
+    #[cfg(feature = "bindings")]
+    pub(crate) fn has_bindings(&self) -> bool {
+        self.bindings.is_some() || self.stylesheet.is_some()
+    }
 }
@@ -1,14 +1,16 @@
 use smallvec::{SmallVec, smallvec};
 
+use crate::ansi::apply_sgr;
 use crate::error::{Error, ErrorKind, Result};
 use crate::parser::{Tag, TagName};
-use crate::shared::Input;
-use crate::style::Style;
+use crate::shared::{Input, Span};
+use crate::style::{Color, Style};
 
 struct Entry<'src, I: ?Sized + Input> {
     name: TagName<'src, I>,
     accum: Style,
-    diff: Style,
+    /// Span of the opening tag.
+    span: Span,
 }
 
 pub struct State<'src, I: ?Sized + Input> {
@@ -21,43 +23,63 @@ impl<'src, I: ?Sized + Input> State<'src, I> {
             stack: smallvec![Entry {
                 name: TagName::Root,
                 accum: Style::new(),
-                diff: Style::new()
+                span: Span::inserted(),
             }],
         }
     }
 
-    pub fn push(&mut self, name: TagName<'src, I>, style: Style) -> Style {
+    /// Pushes the style of an opened tag and returns the style change to write.
+    pub fn push(&mut self, name: TagName<'src, I>, style: Style, span: Span) -> Style {
         let prev_accum = match self.stack.last() {
             Some(entry) => entry.accum,
             None => unreachable!(),
         };
 
-        let accum = prev_accum + style;
-        let diff = style - prev_accum;
+        let accum = if name == TagName::Ansi {
+            apply_sgr(prev_accum, style)
+        } else {
+            prev_accum + style
+        };
+        let diff = if name == TagName::Ansi {
+            // colors unset by a reset are not negated by subtraction
+            revert(accum, prev_accum)
+        } else {
+            accum - prev_accum
+        };
         if name == TagName::Ansi {
             match self.stack.last_mut() {
-                Some(entry) => {
-                    entry.accum = accum;
-                    entry.diff = diff;
-                }
+                Some(entry) => entry.accum = accum,
                 // the stack must contain at least the root element
                 None => unreachable!(),
             }
         } else {
-            self.stack.push(Entry { name, accum, diff });
+            self.stack.push(Entry { name, accum, span });
         }
         diff
     }
 
+    /// Returns the accumulated style of the innermost open tag.
+    pub fn current(&self) -> Style {
+        self.stack
+            .last()
+            .map_or_else(Style::new, |entry| entry.accum)
+    }
+
+    /// Returns the style change that restores the style of the parent of a popped entry.
+    fn restore(&self, entry: &Entry<'src, I>) -> Style {
+        revert(self.current(), entry.accum)
+    }
+
+    /// Pops the entry of a closed tag and returns the style change to write.
     pub fn pop_tag(&mut self, tag: &Tag<'src, I>) -> Result<'src, I, Style> {
-        let lname = match self.stack.last() {
-            Some(entry) => &entry.name,
+        let (lname, lspan) = match self.stack.last() {
+            Some(entry) => (&entry.name, entry.span),
             None => unreachable!(),
         };
 
         if tag.name == *lname || tag.name == TagName::Empty {
             match self.stack.pop() {
-                Some(entry) => Ok(entry.diff),
+                Some(entry) => Ok(self.restore(&entry)),
                 None => unreachable!(),
             }
         } else {
@@ -65,16 +87,32 @@ impl<'src, I: ?Sized + Input> State<'src, I> {
                 kind: ErrorKind::MisMatchedTags {
                     open: lname.clone(),
                     close: tag.name.clone(),
+                    open_span: lspan,
                 },
                 span: tag.span,
             })
         }
     }
 
+    /// Pops the last entry and returns the style change to write.
     pub fn pop(&mut self) -> Option<Style> {
-        match self.stack.pop() {
-            Some(entry) => Some(entry.diff),
-            None => None,
-        }
+        let entry = self.stack.pop()?;
+        Some(self.restore(&entry))
+    }
+}
+
+/// Returns the style change that goes back from `style` to `parent`.
+pub(crate) fn revert(parent: Style, style: Style) -> Style {
+    let mut diff = parent - style;
+    // colors are not negated by subtraction
+    if parent.fg_color() == Color::None {
+        diff.set_fg_color(!style.fg_color());
+    }
+    if parent.bg_color() == Color::None {
+        diff.set_bg_color(!style.bg_color());
+    }
+    if parent.ul_color() == Color::None {
+        diff.set_ul_color(!style.ul_color());
     }
+    diff
 }
@@ -0,0 +1,287 @@
+use std::fmt::{self, Display};
+use std::iter;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::shared::{Input, Span};
+
+use super::{Error, ErrorKind};
+
+const COLORS: [&str; 9] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white", "none",
+];
+
+/// An [`Error`] shown with the source line it occurred on.
+///
+/// ```text
+/// error: mismatched tags: <b>...</i>
+///  --> 1:10
+///   |
+/// 1 | <b>bold</i>
+///   |        ^~~~
+///   |
+/// note: `<b>` is opened here
+///  --> 1:1
+///   |
+/// 1 | <b>bold</i>
+///   | ^~~
+///   = help: close `<b>` with `</b>` before closing `<i>`
+/// ```
+pub struct Diagnostic<'a, 'src, I: ?Sized + Input> {
+    error: &'a Error<'src, I>,
+    source: &'a [u8],
+    styled: bool,
+}
+
+impl<'src, I: ?Sized + Input> Error<'src, I> {
+    /// Returns a [`Diagnostic`] that shows this error in `source`, the input it was
+    /// returned for.
+    #[must_use]
+    pub fn diagnostic<'a>(&'a self, source: &'a I) -> Diagnostic<'a, 'src, I> {
+        Diagnostic {
+            error: self,
+            source: source.as_ref(),
+            styled: false,
+        }
+    }
+}
+
+impl<I: ?Sized + Input> Diagnostic<'_, '_, I> {
+    /// Sets whether the diagnostic is written with ANSI styles.
+    #[must_use]
+    pub fn styled(mut self, styled: bool) -> Self {
+        self.styled = styled;
+        self
+    }
+
+    fn paint(&self, f: &mut fmt::Formatter<'_>, sgr: &str, text: &str) -> fmt::Result {
+        if self.styled {
+            write!(f, "\x1b[{sgr}m{text}\x1b[0m")
+        } else {
+            f.write_str(text)
+        }
+    }
+
+    /// Writes the source line of `span` with an underline below it.
+    fn snippet(&self, f: &mut fmt::Formatter<'_>, span: Span, sgr: &str) -> fmt::Result {
+        let row = span.start.row as usize;
+        let line = self
+            .source
+            .split(|b| *b == b'\n')
+            .nth(row - 1)
+            .unwrap_or(b"");
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end_matches('\r');
+        let gutter = " ".repeat(row.to_string().len());
+
+        let start = (span.start.col as usize).saturating_sub(1).min(line.len());
+        let end = if span.end.row == span.start.row {
+            (span.end.col as usize)
+                .saturating_sub(1)
+                .clamp(start, line.len())
+        } else {
+            line.len()
+        };
+        // the underline is aligned by the columns the characters take in a terminal
+        let mut indent = String::new();
+        for c in line.get(..start).unwrap_or_default().chars() {
+            match c {
+                '\t' => indent.push('\t'),
+                c => indent.extend(iter::repeat_n(' ', c.width().unwrap_or(0))),
+            }
+        }
+        let width: usize = line
+            .get(start..end)
+            .unwrap_or_default()
+            .chars()
+            .map(|c| c.width().unwrap_or(0))
+            .sum();
+        let underline = format!("^{}", "~".repeat(width.saturating_sub(1)));
+
+        // the column counts characters, like the underline
+        let col = line.get(..start).unwrap_or_default().chars().count() + 1;
+        self.paint(f, "1;34", &format!("{gutter}--> "))?;
+        writeln!(f, "{row}:{col}")?;
+        self.paint(f, "1;34", &format!("{gutter} |"))?;
+        writeln!(f)?;
+        self.paint(f, "1;34", &format!("{row} | "))?;
+        writeln!(f, "{line}")?;
+        self.paint(f, "1;34", &format!("{gutter} | "))?;
+        f.write_str(&indent)?;
+        self.paint(f, sgr, &underline)?;
+        writeln!(f)
+    }
+}
+
+impl<I: ?Sized + Display + Input> Display for Diagnostic<'_, '_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.error.span;
+
+        self.paint(f, "1;31", "error")?;
+        self.paint(f, "1", &format!(": {}", self.error.kind))?;
+        writeln!(f)?;
+        if span == Span::inserted() {
+            return Ok(());
+        }
+        self.snippet(f, span, "1;31")?;
+
+        if let ErrorKind::MisMatchedTags {
+            open, open_span, ..
+        } = &self.error.kind
+            && *open_span != Span::inserted()
+        {
+            let gutter = " ".repeat(open_span.start.row.to_string().len());
+            self.paint(f, "1;34", &format!("{gutter} |"))?;
+            writeln!(f)?;
+            self.paint(f, "1", "note")?;
+            writeln!(f, ": `<{open}>` is opened here")?;
+            self.snippet(f, *open_span, "1;34")?;
+        }
+
+        if let Some(help) = help(&self.error.kind) {
+            let gutter = " ".repeat(span.start.row.to_string().len());
+            self.paint(f, "1;34", &format!("{gutter} = "))?;
+            self.paint(f, "1", "help")?;
+            writeln!(f, ": {help}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a note on how to fix an error.
+fn help<I: ?Sized + Display + Input>(kind: &ErrorKind<'_, I>) -> Option<String> {
+    match kind {
+        ErrorKind::MisMatchedTags {
+            close, open_span, ..
+        } if *open_span == Span::inserted() => Some(format!("remove `</{close}>`, no tag is open")),
+        ErrorKind::MisMatchedTags { open, close, .. } => Some(format!(
+            "close `<{open}>` with `</{open}>` before closing `<{close}>`"
+        )),
+        ErrorKind::InvalidColor(color) => {
+            let color = color.to_string().to_lowercase();
+            let nearest = COLORS
+                .iter()
+                .map(|name| (distance(&color, name), name))
+                .min()
+                .filter(|(distance, name)| *distance <= name.len() / 3 + 1);
+            Some(match nearest {
+                Some((_, name)) => format!("did you mean `{name}`?"),
+                None => "a color is a name like `red`, `#rrggbb`, `rgb(r, g, b)` or `fixed(n)`"
+                    .to_string(),
+            })
+        }
+        ErrorKind::InvalidAlign(_) => {
+            Some("an alignment is `left`, `center` or `right`".to_string())
+        }
+        ErrorKind::InvalidBorder(_) => {
+            Some("a border is `single`, `double`, `rounded`, `heavy` or `ascii`".to_string())
+        }
+        ErrorKind::InvalidProgress(_) => {
+            Some("a progress is a fraction from 0 to 1 or a percentage".to_string())
+        }
+        ErrorKind::InvalidUnderline(_) => {
+            Some("an underline is `single`, `double`, `curly`, `dotted` or `dashed`".to_string())
+        }
+        ErrorKind::MissingArgument => {
+            Some("pass an argument for each `{}`, or write `{{` for a brace".to_string())
+        }
+        ErrorKind::InvalidNumber { max, .. } => {
+            Some(format!("the number must be between 0 and {max}"))
+        }
+        ErrorKind::TextOutsideCell(_) => Some("put the text in a `<td>` or a `<th>`".to_string()),
+        ErrorKind::UnterminatedString => {
+            Some("close the string with the quote it was opened with".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr.push((prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::try_style;
+
+    #[test]
+    fn test_diagnostic_mismatched_tags() {
+        let source = "<b>bold\n<i>text</b>";
+        let error = try_style(source).unwrap_err();
+        assert_eq!(
+            error.diagnostic(source).to_string(),
+            "error: mismatched tags: <i>...</b>\n \
+             --> 2:8\n  \
+             |\n\
+             2 | <i>text</b>\n  \
+             |        ^~~~\n  \
+             |\n\
+             note: `<i>` is opened here\n \
+             --> 2:1\n  \
+             |\n\
+             2 | <i>text</b>\n  \
+             | ^~~\n  \
+             = help: close `<i>` with `</i>` before closing `<b>`\n"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_suggests_color() {
+        let source = "<c rgb='1,2,3'>a</c> <b c='gren'>b</b>";
+        let error = try_style(source).unwrap_err();
+        let diagnostic = error.diagnostic(source).to_string();
+        assert!(
+            diagnostic.ends_with("= help: did you mean `green`?\n"),
+            "{diagnostic}"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_aligns_wide_characters() {
+        let source = "你好<b>x</i>";
+        let error = try_style(source).unwrap_err();
+        let diagnostic = error.diagnostic(source).to_string();
+        assert_eq!(
+            diagnostic,
+            "error: mismatched tags: <b>...</i>\n \
+             --> 1:7\n  \
+             |\n\
+             1 | 你好<b>x</i>\n  \
+             |         ^~~~\n  \
+             |\n\
+             note: `<b>` is opened here\n \
+             --> 1:3\n  \
+             |\n\
+             1 | 你好<b>x</i>\n  \
+             |     ^~~\n  \
+             = help: close `<b>` with `</b>` before closing `<i>`\n"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_points_at_attribute() {
+        let source = "<div align='left' width='1001'>a</div>";
+        let error = try_style(source).unwrap_err();
+        assert_eq!(
+            error.diagnostic(source).to_string(),
+            "error: invalid number: `1001`\n \
+             --> 1:25\n  \
+             |\n\
+             1 | <div align='left' width='1001'>a</div>\n  \
+             |                         ^~~~~~\n  \
+             = help: the number must be between 0 and 1000\n"
+        );
+    }
+}
@@ -5,6 +5,10 @@ use crate::parser::TagName;
 use crate::scanner::{Token, TokenKind};
 use crate::shared::{Input, Span};
 
+pub use diagnostic::Diagnostic;
+
+mod diagnostic;
+
 /// Result
 pub type Result<'src, I, T> = std::result::Result<T, Error<'src, I>>;
 
@@ -21,6 +25,12 @@ impl<'src, I: ?Sized + Input> Error<'src, I> {
     pub fn kind(&self) -> &ErrorKind<'src, I> {
         &self.kind
     }
+
+    /// Returns the span in the source where the error occurred.
+    #[must_use]
+    pub fn span(&self) -> Span {
+        self.span
+    }
 }
 
 impl<I: ?Sized + Debug + Input> Debug for Error<'_, I> {
@@ -87,18 +97,34 @@ impl<'src, I: ?Sized + Input> Error<'src, I> {
 /// Represents the different kinds of parse errors.
 pub enum ErrorKind<'src, I: ?Sized + Input> {
     BuiltinTagOverwrite(&'src I),
+    /// Indicates an invalid alignment was encountered.
+    InvalidAlign(&'src I),
+    /// Indicates an invalid border style was encountered.
+    InvalidBorder(&'src I),
     /// Indicates an invalid color was encountered.
     FmtError,
     IoError(io::Error),
     InvalidColor(&'src I),
     /// Indicates an invalid number was encountered.
-    InvalidNumber(&'src I),
+    InvalidNumber {
+        number: &'src I,
+        /// Largest number the attribute accepts.
+        max: u32,
+    },
     InvalidTagName(&'src I),
+    /// Indicates an invalid underline style was encountered.
+    InvalidUnderline(&'src I),
+    /// Indicates an invalid progress value was encountered.
+    InvalidProgress(&'src I),
     /// Mismatched opening and closing tags.
     MisMatchedTags {
         open: TagName<'src, I>,
         close: TagName<'src, I>,
+        /// Span of the opening tag.
+        open_span: Span,
     },
+    /// A placeholder of a template has no argument.
+    MissingArgument,
     /// Indicates the end of input was reached unexpectedly.
     UnexpectedEof,
     /// Indicates an unexpected token was encountered.
@@ -108,6 +134,8 @@ pub enum ErrorKind<'src, I: ?Sized + Input> {
     },
     /// An unknown token was encountered.
     UnknownToken(&'src I),
+    /// Text was written in a table outside of its cells.
+    TextOutsideCell(&'src I),
     /// Indicates an unterminated string literal.
     UnterminatedString,
 }
@@ -118,16 +146,34 @@ impl<'src, I: ?Sized + Debug + Input> Debug for ErrorKind<'src, I> {
             ErrorKind::BuiltinTagOverwrite(arg0) => {
                 f.debug_tuple("BuiltinTagOverwrite").field(arg0).finish()
             }
+            ErrorKind::InvalidAlign(arg0) => f.debug_tuple("InvalidAlign").field(arg0).finish(),
+            ErrorKind::InvalidBorder(arg0) => f.debug_tuple("InvalidBorder").field(arg0).finish(),
             ErrorKind::FmtError => write!(f, "FmtError"),
             ErrorKind::IoError(arg0) => f.debug_tuple("IoError").field(arg0).finish(),
             ErrorKind::InvalidColor(arg0) => f.debug_tuple("InvalidColor").field(arg0).finish(),
-            ErrorKind::InvalidNumber(arg0) => f.debug_tuple("InvalidNumber").field(arg0).finish(),
+            ErrorKind::InvalidNumber { number, max } => f
+                .debug_struct("InvalidNumber")
+                .field("number", number)
+                .field("max", max)
+                .finish(),
             ErrorKind::InvalidTagName(arg0) => f.debug_tuple("InvalidTagName").field(arg0).finish(),
-            ErrorKind::MisMatchedTags { open, close } => f
+            ErrorKind::InvalidUnderline(arg0) => {
+                f.debug_tuple("InvalidUnderline").field(arg0).finish()
+            }
+            ErrorKind::InvalidProgress(arg0) => {
+                f.debug_tuple("InvalidProgress").field(arg0).finish()
+            }
+            ErrorKind::MisMatchedTags {
+                open,
+                close,
+                open_span,
+            } => f
                 .debug_struct("MisMatchedTags")
                 .field("open", open)
                 .field("close", close)
+                .field("open_span", open_span)
                 .finish(),
+            ErrorKind::MissingArgument => write!(f, "MissingArgument"),
             ErrorKind::UnexpectedEof => write!(f, "UnexpectedEof"),
             ErrorKind::UnexpectedToken { expected, found } => f
                 .debug_struct("UnexpectedToken")
@@ -135,6 +181,9 @@ impl<'src, I: ?Sized + Debug + Input> Debug for ErrorKind<'src, I> {
                 .field("found", found)
                 .finish(),
             ErrorKind::UnknownToken(arg0) => f.debug_tuple("UnknownToken").field(arg0).finish(),
+            ErrorKind::TextOutsideCell(arg0) => {
+                f.debug_tuple("TextOutsideCell").field(arg0).finish()
+            }
             ErrorKind::UnterminatedString => write!(f, "UnterminatedString"),
         }
     }
@@ -146,18 +195,31 @@ impl<'src, I: ?Sized + Display + Input> Display for ErrorKind<'src, I> {
             ErrorKind::BuiltinTagOverwrite(name) => {
                 f.write_fmt(format_args!("attempt to overwrite builtin tag: `{name}`"))
             }
+            ErrorKind::InvalidAlign(align) => {
+                f.write_fmt(format_args!("invalid alignment: '{align}'"))
+            }
+            ErrorKind::InvalidBorder(border) => {
+                f.write_fmt(format_args!("invalid border: '{border}'"))
+            }
             ErrorKind::FmtError => f.write_str("format error"),
             ErrorKind::IoError(error) => Display::fmt(&error.kind(), f),
-            ErrorKind::InvalidNumber(number) => {
+            ErrorKind::InvalidNumber { number, .. } => {
                 f.write_fmt(format_args!("invalid number: `{number}`"))
             }
             ErrorKind::InvalidColor(color) => f.write_fmt(format_args!("invalid color: '{color}'")),
             ErrorKind::InvalidTagName(name) => {
                 f.write_fmt(format_args!("invalid tag name: `{name}`"))
             }
-            ErrorKind::MisMatchedTags { open, close } => {
+            ErrorKind::InvalidUnderline(underline) => {
+                f.write_fmt(format_args!("invalid underline: '{underline}'"))
+            }
+            ErrorKind::InvalidProgress(value) => {
+                f.write_fmt(format_args!("invalid progress: '{value}'"))
+            }
+            ErrorKind::MisMatchedTags { open, close, .. } => {
                 f.write_fmt(format_args!("mismatched tags: <{open}>...</{close}>"))
             }
+            ErrorKind::MissingArgument => f.write_str("missing argument for placeholder"),
             ErrorKind::UnexpectedEof => f.write_str("Unexpected Eof"),
             ErrorKind::UnexpectedToken { expected, found } => match found {
                 Some(found) => f.write_fmt(format_args!(
@@ -166,6 +228,9 @@ impl<'src, I: ?Sized + Display + Input> Display for ErrorKind<'src, I> {
                 None => f.write_fmt(format_args!("unexpected token, expected {expected:?}")),
             },
             ErrorKind::UnknownToken(tok) => f.write_fmt(format_args!("Unknown token: {tok}")),
+            ErrorKind::TextOutsideCell(text) => {
+                f.write_fmt(format_args!("text outside of a table cell: `{text}`"))
+            }
             ErrorKind::UnterminatedString => f.write_str("unterminated string"),
         }
     }
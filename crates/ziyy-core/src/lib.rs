@@ -4,11 +4,15 @@
 #![doc = include_str!("../../../README.md")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub use ansi::ansi_to_markup;
 pub use context::Context;
-pub use error::{Error, ErrorKind, Result};
-pub use parser::{Chunk, Tag, TagKind, TagName};
+pub use error::{Diagnostic, Error, ErrorKind, Result};
+pub use parser::{AttrSpans, Chunk, Tag, TagKind, TagName};
 pub use renderer::Renderer;
 pub use shared::{Position, Span, Value};
+#[cfg(feature = "bindings")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bindings")))]
+pub use stylesheet::Stylesheet;
 #[cfg(feature = "tree")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tree")))]
 pub use tree::Tree;
@@ -16,6 +20,7 @@ pub use tree::Tree;
 #[macro_use]
 mod macros;
 
+mod ansi;
 mod builtins;
 mod context;
 mod error;
@@ -25,6 +30,8 @@ pub mod renderer;
 mod scanner;
 mod shared;
 pub mod style;
+#[cfg(feature = "bindings")]
+mod stylesheet;
 #[cfg(feature = "tree")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tree")))]
 pub mod tree;
@@ -71,6 +78,37 @@ pub fn try_style(text: &str) -> Result<'_, str, std::string::String> {
     renderer.render(text)
 }
 
+/// Styles the given template using ziyy, replacing each `{}` with the next argument.
+///
+/// Arguments are inserted as plain text and never parsed as markup, so untrusted text can
+/// not open tags or write escape sequences. `{{` and `}}` are written as `{` and `}`.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> ziyy_core::Result<'static, str, ()> {
+/// # use ziyy_core as ziyy;
+/// use ziyy::format_styled;
+///
+/// let name = "<b>admin</b>";
+/// let styled_text = format_styled("<c red>user {} not found", &[&name])?;
+/// assert!(styled_text.contains("<b>admin</b>"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the template is malformed or has more placeholders than arguments.
+#[inline]
+pub fn format_styled<'src>(
+    template: &'src str,
+    args: &[&dyn std::fmt::Display],
+) -> Result<'src, str, std::string::String> {
+    let renderer = Renderer::new(String::new());
+    renderer.render_args(template, args)
+}
+
 #[must_use]
 #[inline]
 #[cfg(feature = "tree")]
@@ -5,10 +5,10 @@ macro_rules! get_num {
 }
 
 macro_rules! get_num2 {
-    ( $kind:expr, $tag:expr ) => {
+    ( $kind:expr, $span:expr ) => {
         $kind.map_err(|k| $crate::error::Error {
             kind: k,
-            span: $tag.span,
+            span: $span,
         })?
     };
 }
@@ -1,6 +1,24 @@
 use crate::error::ErrorKind;
 use crate::shared::Input;
 
+/// Largest number of columns, spaces or lines a layout attribute accepts.
+pub const MAX_SIZE: u16 = 1000;
+
+/// Reads a layout attribute, a number of columns, spaces or lines up to [`MAX_SIZE`].
+#[inline]
+pub fn input_to_size<I: ?Sized + Input>(input: &I) -> Result<usize, ErrorKind<'_, I>> {
+    let n = str::from_utf8(input.as_ref())
+        .ok()
+        .and_then(|s| s.parse::<u16>().ok());
+    match n {
+        Some(n) if n <= MAX_SIZE => Ok(usize::from(n)),
+        _ => Err(ErrorKind::InvalidNumber {
+            number: input,
+            max: MAX_SIZE.into(),
+        }),
+    }
+}
+
 #[inline]
 pub fn input_to_u8<I: ?Sized + Input>(input: &I, radix: u32) -> Result<u8, ErrorKind<'_, I>> {
     // SAFETY: input contains only bytes in ASCII range as this function
@@ -8,7 +26,10 @@ pub fn input_to_u8<I: ?Sized + Input>(input: &I, radix: u32) -> Result<u8, Error
     unsafe {
         match u8::from_str_radix(str::from_utf8_unchecked(input.as_ref()), radix) {
             Ok(n) => Ok(n),
-            Err(_) => Err(ErrorKind::InvalidNumber(input)),
+            Err(_) => Err(ErrorKind::InvalidNumber {
+                number: input,
+                max: u8::MAX.into(),
+            }),
         }
     }
 }
@@ -20,7 +41,10 @@ pub fn input_to_u32<I: ?Sized + Input>(input: &I, radix: u32) -> Result<u32, Err
     unsafe {
         match u32::from_str_radix(str::from_utf8_unchecked(input.as_ref()), radix) {
             Ok(n) => Ok(n),
-            Err(_) => Err(ErrorKind::InvalidNumber(input)),
+            Err(_) => Err(ErrorKind::InvalidNumber {
+                number: input,
+                max: u32::MAX,
+            }),
         }
     }
 }
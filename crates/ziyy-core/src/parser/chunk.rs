@@ -5,6 +5,8 @@ use crate::TagName;
 
 use super::Tag;
 
+// tags are parsed once and moved, boxing them would allocate for every tag
+#[allow(clippy::large_enum_variant)]
 #[derive(PartialEq)]
 pub enum Chunk<'src, I: ?Sized + Input> {
     Comment(&'src I, Span),
@@ -6,20 +6,25 @@ use crate::error::Error;
 use crate::error::ErrorKind;
 use crate::error::Result;
 use crate::scanner::{Scanner, Token, TokenKind};
-use crate::shared::Input;
 use crate::shared::Value;
+use crate::shared::{Input, Span};
 use crate::style::AnsiColor;
 use crate::style::{
-    Ansi256, Blink, Color, Delete, FontStyle, Hide, Intensity, Invert, Rgb, Style, Underline,
+    Ansi256, Blink, Color, Delete, FontStyle, Hide, Intensity, Invert, Rgb, Underline,
 };
+#[cfg(feature = "uncommon")]
+use crate::style::{Font, Frame, Overline, PropSpace};
 
 use TokenKind::{
-    A, B, BLACK, BLUE, BR, C, CLASS, CODE, CURLY, CYAN, D, DASHED, DIV, DOTTED, DOUBLE, FIXED,
-    GREAT, GREEN, H, HEX, HREF, I, ID, IDENTIFIER, INDENT, K, LET, MAGENTA, N, NONE, NUMBER, P,
-    PRE, R, RED, RGB, S, SINGLE, SPAN, U, UU, WHITE, X, YELLOW, ZIYY,
+    A, ALIGN, B, BLACK, BLUE, BORDER, BORDER_COLOR, BOX, BR, BULLET, C, CHAR, CLASS, CODE, CURLY,
+    CYAN, D, DASHED, DIV, DOTTED, DOUBLE, EMPTY, EMPTY_COLOR, ENCIRCLED, FAST, FILL, FILL_COLOR,
+    FIXED, FONT, FRAKTUR, FRAMED, FROM, GRADIENT, GREAT, GREEN, H, H1, H2, H3, HEX, HR, HREF, I,
+    ID, IDENTIFIER, INDENT, K, LET, LI, MAGENTA, MARGIN, N, NONE, NUMBER, O, OL, P, PAD, PRE,
+    PROGRESS, PROPORTIONAL, R, RED, RGB, S, SINGLE, SPAN, START, STOPS, TABLE, TD, TH, TITLE, TO,
+    TR, U, UL, UU, VALUE, WHITE, WIDTH, X, YELLOW, ZIYY,
 };
 pub use chunk::Chunk;
-pub use tag::{Tag, TagKind, TagName};
+pub use tag::{AttrSpans, Tag, TagKind, TagName};
 
 mod chunk;
 #[macro_use]
@@ -87,10 +92,15 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                         &token.content[2..token.content.as_ref().len() - 1],
                         token.span,
                     ))),
-                    TokenKind::ANSI_ESC => Ok(Chunk::Tag(Tag::parse_from_ansi(
-                        &token.content[5..token.content.as_ref().len() - 1],
-                        token.span,
-                    ))),
+                    TokenKind::ANSI_ESC => {
+                        // the parameters follow `\e[` or `\x1b[`
+                        let content = token.content.as_ref();
+                        let start = content.iter().position(|b| *b == b'[').unwrap_or(0) + 1;
+                        Ok(Chunk::Tag(Tag::parse_from_ansi(
+                            &token.content[start..content.len() - 1],
+                            token.span,
+                        )))
+                    }
 
                     _ => Err(Error::new(
                         ErrorKind::UnexpectedToken {
@@ -103,9 +113,9 @@ impl<'src, I: ?Sized + Input> Parser<I> {
             }
         };
 
+        let start = token.span;
         let token = ctx.scanner.scan_token()?;
 
-        let mut style = Style::new();
         let tag_name = match token.kind {
             GREAT if kind == TagKind::Open => {
                 return Ok(Chunk::Tag(Tag::new(TagName::Empty, kind)));
@@ -118,7 +128,23 @@ impl<'src, I: ?Sized + Input> Parser<I> {
         };
 
         let mut tag = Tag::new(tag_name.clone(), kind);
-        tag.span = token.span;
+        tag.span = start + token.span;
+        if token.kind == UU {
+            tag.style.set_underline(Underline::Double);
+        }
+        if let Err(error) = Parser::parse_attributes(ctx, &mut tag) {
+            ctx.malformed_tag = Some(tag);
+            return Err(error);
+        }
+
+        Ok(Chunk::Tag(tag))
+    }
+
+    /// Parses the attributes of a tag up to the closing `>` or `/>`.
+    #[allow(clippy::too_many_lines)]
+    fn parse_attributes(ctx: &mut Context<'src, I>, tag: &mut Tag<'src, I>) -> Result<'src, I, ()> {
+        let tag_name = tag.name.clone();
+        let mut style = tag.style;
 
         let mut token = ctx.scanner.scan_token()?;
         tag.span += token.span;
@@ -159,6 +185,7 @@ impl<'src, I: ?Sized + Input> Parser<I> {
         macro_rules! assign_prop_value {
             ( $prop:tt ) => {{
                 tag.$prop = Value::Bool;
+                tag.spans.$prop = token.span;
 
                 token = ctx.scanner.scan_token()?;
                 tag.span += token.span;
@@ -168,6 +195,7 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                     expect_token(&token, TokenKind::STRING)?;
                     let end = token.content.as_ref().len() - 1;
                     tag.$prop = Value::Some(&token.content[1..end]);
+                    tag.spans.$prop = token.span;
                     token = ctx.scanner.scan_token()?;
                 }
             }};
@@ -198,7 +226,21 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                     assign_effect!(set_font_style, FontStyle::Italics);
                 }
                 U => {
-                    assign_effect!(set_underline, Underline::Single);
+                    style.set_underline(Underline::Single);
+
+                    token = ctx.scanner.scan_token()?;
+                    tag.span += token.span;
+                    if token.kind == TokenKind::EQUAL {
+                        token = ctx.scanner.scan_token()?;
+                        tag.span += token.span;
+                        expect_token(&token, TokenKind::STRING)?;
+
+                        let end = token.content.as_ref().len() - 1;
+                        let underline = parse_underline(&token.content[1..end], token.span)?;
+                        style.set_underline(underline);
+                        token = ctx.scanner.scan_token()?;
+                        tag.span += token.span;
+                    }
                 }
                 K => {
                     assign_effect!(set_blink, Blink::Slow);
@@ -215,9 +257,82 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                 UU => {
                     assign_effect!(set_underline, Underline::Double);
                 }
-                DOUBLE => {
+
+                // uncommon styles
+                #[cfg(feature = "uncommon")]
+                O => {
+                    assign_effect!(set_overline, Overline::Set);
+                }
+                #[cfg(feature = "uncommon")]
+                FRAMED => {
+                    assign_effect!(set_frame, Frame::Framed);
+                }
+                #[cfg(feature = "uncommon")]
+                ENCIRCLED => {
+                    assign_effect!(set_frame, Frame::Encircled);
+                }
+                #[cfg(feature = "uncommon")]
+                PROPORTIONAL => {
+                    assign_effect!(set_prop_space, PropSpace::Set);
+                }
+                #[cfg(feature = "uncommon")]
+                FONT => {
+                    token = ctx.scanner.scan_token()?;
+                    expect_token(&token, TokenKind::EQUAL)?;
+                    tag.span += token.span;
+
+                    token = ctx.scanner.scan_token()?;
+                    expect_token(&token, TokenKind::STRING)?;
+                    tag.span += token.span;
+
+                    let end = token.content.as_ref().len() - 1;
+                    let s = &token.content[1..end];
+
+                    let mut scanner = Scanner::new(s);
+                    scanner.text_mode = false;
+                    scanner.current_pos = token.span.start;
+
+                    let tok = scanner.scan_token()?;
+                    let Some(font) = Font::from_number(number!(tok.content, 10, &tok)) else {
+                        let kind = ErrorKind::InvalidNumber {
+                            number: tok.content,
+                            max: 9,
+                        };
+                        return Err(Error::new(kind, &tok));
+                    };
+                    style.set_font(font);
+
+                    let tok = scanner.scan_token()?;
+                    expect_token(&tok, TokenKind::EOF)?;
+
+                    token = ctx.scanner.scan_token()?;
+                }
+                #[cfg(not(feature = "uncommon"))]
+                O | FRAMED | ENCIRCLED | PROPORTIONAL | FONT => {
+                    consume_declaration!();
+                }
+
+                FRAKTUR => {
+                    assign_effect!(set_font_style, FontStyle::Fraktur);
+                }
+                FAST => {
+                    if tag_name == TagName::K {
+                        assign_effect!(set_blink, Blink::Fast);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+
+                SINGLE | DOUBLE | CURLY | DOTTED | DASHED => {
                     if tag_name == TagName::U {
-                        assign_effect!(set_underline, Underline::Double);
+                        let underline = match token.kind {
+                            SINGLE => Underline::Single,
+                            DOUBLE => Underline::Double,
+                            CURLY => Underline::Curly,
+                            DOTTED => Underline::Dotted,
+                            _ => Underline::Dashed,
+                        };
+                        assign_effect!(set_underline, underline);
                     } else {
                         consume_declaration!();
                     }
@@ -227,9 +342,16 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                 C => {
                     assign_color!(set_fg_color);
                 }
+                X if tag_name == TagName::Gradient => {
+                    // the gradient colors the background
+                    assign_prop_value!(custom);
+                }
                 X => {
                     assign_color!(set_bg_color);
                 }
+                UL => {
+                    assign_color!(set_ul_color);
+                }
                 BLACK | BLUE | CYAN | GREEN | MAGENTA | RED | WHITE | YELLOW => {
                     let mut color = Color::parse(token.content, token.span)?;
 
@@ -361,6 +483,14 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                         consume_declaration!();
                     }
                 }
+                CHAR => {
+                    // character a rule is drawn with
+                    if tag_name == TagName::Hr {
+                        assign_prop_value!(custom);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
                 HREF => {
                     // url of link
                     if tag_name == TagName::A {
@@ -374,6 +504,7 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                     if tag_name == TagName::Let {
                         {
                             tag.custom = Value::Bool;
+                            tag.spans.custom = token.span;
                             token = ctx.scanner.scan_token()?;
                             tag.span += token.span;
                             if token.kind == TokenKind::EQUAL {
@@ -389,6 +520,7 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                                     });
                                 }
                                 tag.custom = Value::Some(s);
+                                tag.spans.custom = token.span;
                                 token = ctx.scanner.scan_token()?;
                             }
                         };
@@ -405,6 +537,115 @@ impl<'src, I: ?Sized + Input> Parser<I> {
                     }
                 }
 
+                WIDTH => {
+                    // number of columns the lines of a block are wrapped at
+                    if matches!(
+                        tag_name,
+                        TagName::Div
+                            | TagName::P
+                            | TagName::Pre
+                            | TagName::Ziyy
+                            | TagName::Box
+                            | TagName::Progress
+                    ) {
+                        assign_prop_value!(width);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+
+                ALIGN if matches!(tag_name, TagName::Td | TagName::Th) => {
+                    // alignment of the content of a cell
+                    assign_prop_value!(align);
+                }
+                ALIGN | PAD | MARGIN => {
+                    // layout of the lines of a block
+                    if matches!(
+                        tag_name,
+                        TagName::Div
+                            | TagName::P
+                            | TagName::H1
+                            | TagName::H2
+                            | TagName::H3
+                            | TagName::Box
+                    ) {
+                        match token.kind {
+                            ALIGN => assign_prop_value!(align),
+                            PAD => assign_prop_value!(pad),
+                            _ => assign_prop_value!(margin),
+                        }
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+
+                BULLET => {
+                    // marker of the items of a list
+                    if tag_name == TagName::Ul {
+                        assign_prop_value!(custom);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+                START => {
+                    // number of the first item of a list
+                    if tag_name == TagName::Ol {
+                        assign_prop_value!(custom);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+                BORDER => {
+                    // style of the border of a table or a box
+                    if matches!(tag_name, TagName::Table | TagName::Box) {
+                        assign_prop_value!(custom);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+                FROM | TO | STOPS => {
+                    // colors of a gradient
+                    if tag_name == TagName::Gradient {
+                        match token.kind {
+                            FROM => assign_prop_value!(from),
+                            TO => assign_prop_value!(to),
+                            _ => assign_prop_value!(stops),
+                        }
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+                VALUE | FILL | EMPTY | FILL_COLOR | EMPTY_COLOR => {
+                    // progress bar
+                    if tag_name == TagName::Progress {
+                        match token.kind {
+                            VALUE => assign_prop_value!(custom),
+                            FILL => assign_prop_value!(fill),
+                            EMPTY => assign_prop_value!(empty),
+                            FILL_COLOR => assign_prop_value!(fill_color),
+                            _ => assign_prop_value!(empty_color),
+                        }
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+                BORDER_COLOR => {
+                    // color of the border of a box
+                    if tag_name == TagName::Box {
+                        assign_prop_value!(border_color);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+                TITLE => {
+                    // title of a box or a rule
+                    if matches!(tag_name, TagName::Box | TagName::Hr) {
+                        assign_prop_value!(title);
+                    } else {
+                        consume_declaration!();
+                    }
+                }
+
                 // inherit properties from binding with name
                 CLASS => assign_prop_value!(class),
 
@@ -445,7 +686,7 @@ impl<'src, I: ?Sized + Input> Parser<I> {
             }
         }
 
-        Ok(Chunk::Tag(tag))
+        Ok(())
     }
 
     pub fn parse_next(ctx: &mut Context<'src, I>) -> Result<'src, I, Chunk<'src, I>> {
@@ -481,32 +722,76 @@ fn expect_token<'src, I: ?Sized + Input>(
     Ok(())
 }
 
+/// Parses the value of the `u` attribute.
+fn parse_underline<I: ?Sized + Input>(source: &I, span: Span) -> Result<'_, I, Underline> {
+    let mut scanner = Scanner::new(source);
+    scanner.text_mode = false;
+    scanner.current_pos = span.start;
+
+    let token = scanner.scan_token()?;
+    let underline = match token.kind {
+        SINGLE => Underline::Single,
+        DOUBLE | UU => Underline::Double,
+        CURLY => Underline::Curly,
+        DOTTED => Underline::Dotted,
+        DASHED => Underline::Dashed,
+        _ => {
+            return Err(Error {
+                kind: ErrorKind::InvalidUnderline(source),
+                span,
+            });
+        }
+    };
+
+    let token = scanner.scan_token()?;
+    expect_token(&token, TokenKind::EOF)?;
+
+    Ok(underline)
+}
+
 pub(crate) fn match_tag_name<'src, I: ?Sized + Input>(
     token: &Token<'src, I>,
 ) -> Result<'src, I, TagName<'src, I>> {
     let kind = match token.kind {
         A => TagName::A,
         B => TagName::B,
+        BOX => TagName::Box,
         BR => TagName::Br,
         C => TagName::C,
         CODE => TagName::Code,
         D => TagName::D,
         DIV => TagName::Div,
+        GRADIENT => TagName::Gradient,
         H => TagName::H,
+        H1 => TagName::H1,
+        H2 => TagName::H2,
+        H3 => TagName::H3,
+        HR => TagName::Hr,
         I => TagName::I,
         K => TagName::K,
         LET => TagName::Let,
+        LI => TagName::Li,
+        OL => TagName::Ol,
         P => TagName::P,
         PRE => TagName::Pre,
+        PROGRESS => TagName::Progress,
         R => TagName::R,
         S => TagName::S,
         SPAN => TagName::Span,
-        U => TagName::U,
+        TABLE => TagName::Table,
+        TD => TagName::Td,
+        TH => TagName::Th,
+        TR => TagName::Tr,
+        U | UU => TagName::U,
+        UL => TagName::Ul,
         X => TagName::X,
         ZIYY => TagName::Ziyy,
 
-        IDENTIFIER | BLACK | BLUE | CYAN | GREEN | MAGENTA | RED | WHITE | YELLOW | FIXED | RGB
-        | CLASS | CURLY | DASHED | DOUBLE | DOTTED | ID | INDENT | HREF | N | NONE | SINGLE => {
+        IDENTIFIER | ALIGN | BLACK | BORDER | BORDER_COLOR | BULLET | CHAR | EMPTY
+        | EMPTY_COLOR | FILL | FILL_COLOR | FROM | STOPS | TO | VALUE | BLUE | CYAN | GREEN
+        | MAGENTA | RED | WHITE | YELLOW | FIXED | RGB | CLASS | CURLY | DASHED | DOUBLE
+        | DOTTED | ENCIRCLED | FAST | FONT | FRAKTUR | FRAMED | ID | INDENT | HREF | MARGIN | N
+        | NONE | O | PAD | PROPORTIONAL | SINGLE | START | TITLE | WIDTH => {
             TagName::Any(token.content)
         }
         _ => {
@@ -557,6 +842,7 @@ mod tests {
             ErrorKind::MisMatchedTags {
                 open: TagName::P,
                 close: tag.name.clone(),
+                open_span: Span::default(),
             },
         );
         assert!(result.is_ok());
@@ -579,6 +865,7 @@ mod tests {
             ErrorKind::MisMatchedTags {
                 open: TagName::P,
                 close: tag.name.clone(),
+                open_span: Span::default(),
             },
         );
         assert!(result.is_err());
@@ -5,8 +5,8 @@ use std::fmt::{Debug, Display};
 use crate::num::input_to_u8;
 use crate::shared::{Input, Span, Value};
 use crate::style::{
-    Ansi256, AnsiColor, Blink, Color, Delete, FontStyle, Hide, Intensity, Invert, Rgb, Style,
-    Underline,
+    Ansi256, AnsiColor, Blink, Color, Delete, FontStyle, Hide, Intensity, Invert, Reset, Rgb,
+    Style, Underline,
 };
 #[cfg(feature = "uncommon")]
 use crate::style::{Font, Frame, Overline, PropSpace, Reserved1, Reserved2};
@@ -24,10 +24,83 @@ pub struct Tag<'src, I: ?Sized + Input> {
     pub style: Style,
     /// Class.
     pub class: Value<'src, I>,
+    /// Width the lines of a block are wrapped at.
+    pub width: Value<'src, I>,
+    /// Alignment of the lines of a block.
+    pub align: Value<'src, I>,
+    /// Spaces added inside both sides of a block.
+    pub pad: Value<'src, I>,
+    /// Spaces added outside both sides of a block.
+    pub margin: Value<'src, I>,
+    /// Title of a box or a rule.
+    pub title: Value<'src, I>,
+    /// Color of the border of a box.
+    pub border_color: Value<'src, I>,
+    /// First color of a gradient.
+    pub from: Value<'src, I>,
+    /// Last color of a gradient.
+    pub to: Value<'src, I>,
+    /// Colors of a gradient between its first and last color.
+    pub stops: Value<'src, I>,
+    /// Character the filled part of a progress bar is drawn with.
+    pub fill: Value<'src, I>,
+    /// Character the empty part of a progress bar is drawn with.
+    pub empty: Value<'src, I>,
+    /// Color of the filled part of a progress bar.
+    pub fill_color: Value<'src, I>,
+    /// Color of the empty part of a progress bar.
+    pub empty_color: Value<'src, I>,
+    /// Spans of the attributes.
+    pub spans: AttrSpans,
     /// Span
     pub span: Span,
 }
 
+/// Spans of the attributes of a [`Tag`], where errors in their values are reported.
+///
+/// The span of an attribute is the span of its value, or of its name if it has no value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttrSpans {
+    pub custom: Span,
+    pub class: Span,
+    pub width: Span,
+    pub align: Span,
+    pub pad: Span,
+    pub margin: Span,
+    pub title: Span,
+    pub border_color: Span,
+    pub from: Span,
+    pub to: Span,
+    pub stops: Span,
+    pub fill: Span,
+    pub empty: Span,
+    pub fill_color: Span,
+    pub empty_color: Span,
+}
+
+impl AttrSpans {
+    const fn new() -> Self {
+        let span = Span::inserted();
+        AttrSpans {
+            custom: span,
+            class: span,
+            width: span,
+            align: span,
+            pad: span,
+            margin: span,
+            title: span,
+            border_color: span,
+            from: span,
+            to: span,
+            stops: span,
+            fill: span,
+            empty: span,
+            fill_color: span,
+            empty_color: span,
+        }
+    }
+}
+
 impl<'src, I: ?Sized + Input> Tag<'src, I> {
     /// Creates new Tag
     #[must_use]
@@ -36,7 +109,7 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
         let mut style = Style::new();
 
         match name {
-            TagName::B => {
+            TagName::B | TagName::Th => {
                 style.set_prev_intensity(style.intensity());
                 style.set_intensity(Intensity::Bold);
             }
@@ -59,6 +132,20 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
             custom: Value::None,
             style,
             class: Value::None,
+            width: Value::None,
+            align: Value::None,
+            pad: Value::None,
+            margin: Value::None,
+            title: Value::None,
+            border_color: Value::None,
+            from: Value::None,
+            to: Value::None,
+            stops: Value::None,
+            fill: Value::None,
+            empty: Value::None,
+            fill_color: Value::None,
+            empty_color: Value::None,
+            spans: AttrSpans::new(),
             span: Span::initial(),
         }
     }
@@ -70,6 +157,20 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
             custom: Value::None,
             style: Style::new(),
             class: Value::None,
+            width: Value::None,
+            align: Value::None,
+            pad: Value::None,
+            margin: Value::None,
+            title: Value::None,
+            border_color: Value::None,
+            from: Value::None,
+            to: Value::None,
+            stops: Value::None,
+            fill: Value::None,
+            empty: Value::None,
+            fill_color: Value::None,
+            empty_color: Value::None,
+            spans: AttrSpans::new(),
             span: Span::inserted(),
         }
     }
@@ -111,7 +212,7 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
 
     #[allow(clippy::too_many_lines)]
     pub(crate) fn parse_from_ansi(source: &'src I, span: Span) -> Self {
-        let mut parts = source.as_ref().split(|n| *n == b';').peekable();
+        let mut parts = source.as_ref().split(|n| *n == b';');
 
         let mut style = Style::default();
 
@@ -121,7 +222,10 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
             let Some(part) = part else { break };
 
             match part {
-                b"" | b"0" => style = Style::default(),
+                b"" | b"0" => {
+                    style = Style::default();
+                    style.set_reset(Reset::Yes);
+                }
                 b"1" => style.set_intensity(Intensity::Bold),
                 b"2" => style.set_intensity(Intensity::Dim),
                 b"3" => style.set_font_style(FontStyle::Italics),
@@ -174,44 +278,8 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
                 b"36" => style.set_fg_color(Color::AnsiColor(AnsiColor::Cyan)),
                 b"37" => style.set_fg_color(Color::AnsiColor(AnsiColor::White)),
                 b"38" => {
-                    if part == b"2" {
-                        let r = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        let g = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        let b = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        style.set_fg_color(Color::Rgb(Rgb(r, g, b)));
-                    }
-
-                    if part == b"5" {
-                        let fixed = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        style.set_fg_color(Color::Ansi256(Ansi256(fixed)));
+                    if let Some(color) = parse_extended_color(&mut parts) {
+                        style.set_fg_color(color);
                     }
                 }
                 b"39" => style.set_fg_color(Color::Unset),
@@ -224,44 +292,8 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
                 b"46" => style.set_bg_color(Color::AnsiColor(AnsiColor::Cyan)),
                 b"47" => style.set_bg_color(Color::AnsiColor(AnsiColor::White)),
                 b"48" => {
-                    if part == b"2" {
-                        let r = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        let g = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        let b = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        style.set_fg_color(Color::Rgb(Rgb(r, g, b)));
-                    }
-
-                    if part == b"5" {
-                        let fixed = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        style.set_bg_color(Color::Ansi256(Ansi256(fixed)));
+                    if let Some(color) = parse_extended_color(&mut parts) {
+                        style.set_bg_color(color);
                     }
                 }
                 b"49" => style.set_bg_color(Color::Unset),
@@ -282,44 +314,8 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
                 #[cfg(feature = "uncommon")]
                 b"57" => style.set_reserved2(Reserved2::Yes),
                 b"58" => {
-                    if part == b"2" {
-                        let r = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        let g = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        let b = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        style.set_ul_color(Color::Rgb(Rgb(r, g, b)));
-                    }
-
-                    if part == b"5" {
-                        let fixed = match parts.next() {
-                            Some(s) => match input_to_u8(s, 10) {
-                                Ok(n) => n,
-                                Err(_) => continue,
-                            },
-                            None => continue,
-                        };
-
-                        style.set_ul_color(Color::Ansi256(Ansi256(fixed)));
+                    if let Some(color) = parse_extended_color(&mut parts) {
+                        style.set_ul_color(color);
                     }
                 }
                 b"59" => style.set_ul_color(Color::Unset),
@@ -351,11 +347,37 @@ impl<'src, I: ?Sized + Input> Tag<'src, I> {
             custom: Value::Some(source),
             style,
             class: Value::None,
+            width: Value::None,
+            align: Value::None,
+            pad: Value::None,
+            margin: Value::None,
+            title: Value::None,
+            border_color: Value::None,
+            from: Value::None,
+            to: Value::None,
+            stops: Value::None,
+            fill: Value::None,
+            empty: Value::None,
+            fill_color: Value::None,
+            empty_color: Value::None,
+            spans: AttrSpans::new(),
             span,
         }
     }
 }
 
+/// Parses the parameters of an extended color (`5;n` or `2;r;g;b`)
+/// following SGR 38, 48 or 58.
+fn parse_extended_color<'a>(parts: &mut impl Iterator<Item = &'a [u8]>) -> Option<Color> {
+    let mut next = || input_to_u8(parts.next()?, 10).ok();
+
+    match next()? {
+        2 => Some(Color::Rgb(Rgb(next()?, next()?, next()?))),
+        5 => Some(Color::Ansi256(Ansi256(next()?))),
+        _ => None,
+    }
+}
+
 impl<I: ?Sized + Debug + Input> Debug for Tag<'_, I> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Tag")
@@ -364,6 +386,20 @@ impl<I: ?Sized + Debug + Input> Debug for Tag<'_, I> {
             .field("custom", &self.custom)
             .field("style", &self.style)
             .field("class", &self.class)
+            .field("width", &self.width)
+            .field("align", &self.align)
+            .field("pad", &self.pad)
+            .field("margin", &self.margin)
+            .field("title", &self.title)
+            .field("border_color", &self.border_color)
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("stops", &self.stops)
+            .field("fill", &self.fill)
+            .field("empty", &self.empty)
+            .field("fill_color", &self.fill_color)
+            .field("empty_color", &self.empty_color)
+            .field("spans", &self.spans)
             .field("span", &self.span)
             .finish()
     }
@@ -395,6 +431,20 @@ impl<I: ?Sized + Input> Clone for Tag<'_, I> {
             custom: self.custom.clone(),
             style: self.style.clone(),
             class: self.class.clone(),
+            width: self.width.clone(),
+            align: self.align.clone(),
+            pad: self.pad.clone(),
+            margin: self.margin.clone(),
+            title: self.title.clone(),
+            border_color: self.border_color.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            stops: self.stops.clone(),
+            fill: self.fill.clone(),
+            empty: self.empty.clone(),
+            fill_color: self.fill_color.clone(),
+            empty_color: self.empty_color.clone(),
+            spans: self.spans,
             span: self.span.clone(),
         }
     }
@@ -413,21 +463,35 @@ pub enum TagName<'src, I: ?Sized + Input> {
     Any(&'src I),
     Ansi,
     B,
+    Box,
     Br,
     C,
     Code,
     D,
     Div,
+    Gradient,
     H,
+    H1,
+    H2,
+    H3,
+    Hr,
     K,
     I,
     Let,
+    Li,
+    Ol,
     P,
     Pre,
+    Progress,
     R,
     S,
     Span,
+    Table,
+    Td,
+    Th,
+    Tr,
     U,
+    Ul,
     X,
     Ziyy,
     Root,
@@ -442,21 +506,35 @@ impl<I: ?Sized + Debug + Input> Debug for TagName<'_, I> {
             TagName::Any(arg0) => f.debug_tuple("Any").field(arg0).finish(),
             TagName::Ansi => write!(f, "Ansi"),
             TagName::B => write!(f, "B"),
+            TagName::Box => write!(f, "Box"),
             TagName::Br => write!(f, "Br"),
             TagName::C => write!(f, "C"),
             TagName::Code => write!(f, "Code"),
             TagName::D => write!(f, "D"),
             TagName::Div => write!(f, "Div"),
+            TagName::Gradient => write!(f, "Gradient"),
             TagName::H => write!(f, "H"),
+            TagName::H1 => write!(f, "H1"),
+            TagName::H2 => write!(f, "H2"),
+            TagName::H3 => write!(f, "H3"),
+            TagName::Hr => write!(f, "Hr"),
             TagName::K => write!(f, "K"),
             TagName::I => write!(f, "I"),
             TagName::Let => write!(f, "Let"),
+            TagName::Li => write!(f, "Li"),
+            TagName::Ol => write!(f, "Ol"),
             TagName::P => write!(f, "P"),
             TagName::Pre => write!(f, "Pre"),
+            TagName::Progress => write!(f, "Progress"),
             TagName::R => write!(f, "R"),
             TagName::S => write!(f, "S"),
             TagName::Span => write!(f, "Span"),
+            TagName::Table => write!(f, "Table"),
+            TagName::Td => write!(f, "Td"),
+            TagName::Th => write!(f, "Th"),
+            TagName::Tr => write!(f, "Tr"),
             TagName::U => write!(f, "U"),
+            TagName::Ul => write!(f, "Ul"),
             TagName::X => write!(f, "X"),
             TagName::Ziyy => write!(f, "Ziyy"),
             TagName::Root => write!(f, "Root"),
@@ -472,21 +550,35 @@ impl<I: ?Sized + Display + Input> Display for TagName<'_, I> {
             TagName::Any(any) => return any.fmt(f),
             TagName::Ansi => "[ansi]",
             TagName::B => "b",
+            TagName::Box => "box",
             TagName::Br => "br",
             TagName::C => "c",
             TagName::Code => "code",
             TagName::D => "d",
             TagName::Div => "div",
+            TagName::Gradient => "gradient",
             TagName::H => "h",
+            TagName::H1 => "h1",
+            TagName::H2 => "h2",
+            TagName::H3 => "h3",
+            TagName::Hr => "hr",
             TagName::I => "i",
             TagName::K => "k",
             TagName::Let => "let",
+            TagName::Li => "li",
+            TagName::Ol => "ol",
             TagName::P => "p",
             TagName::Pre => "pre",
+            TagName::Progress => "progress",
             TagName::R => "r",
             TagName::S => "s",
             TagName::Span => "span",
+            TagName::Table => "table",
+            TagName::Td => "td",
+            TagName::Th => "th",
+            TagName::Tr => "tr",
             TagName::U => "u",
+            TagName::Ul => "ul",
             TagName::X => "x",
             TagName::Ziyy => "ziyy",
             TagName::Root => "[root]",
@@ -502,21 +594,35 @@ impl<I: ?Sized + Input> Clone for TagName<'_, I> {
             TagName::Any(arg0) => TagName::Any(*arg0),
             TagName::Ansi => TagName::Ansi,
             TagName::B => TagName::B,
+            TagName::Box => TagName::Box,
             TagName::Br => TagName::Br,
             TagName::C => TagName::C,
             TagName::Code => TagName::Code,
             TagName::D => TagName::D,
             TagName::Div => TagName::Div,
+            TagName::Gradient => TagName::Gradient,
             TagName::H => TagName::H,
+            TagName::H1 => TagName::H1,
+            TagName::H2 => TagName::H2,
+            TagName::H3 => TagName::H3,
+            TagName::Hr => TagName::Hr,
             TagName::K => TagName::K,
             TagName::I => TagName::I,
             TagName::Let => TagName::Let,
+            TagName::Li => TagName::Li,
+            TagName::Ol => TagName::Ol,
             TagName::P => TagName::P,
             TagName::Pre => TagName::Pre,
+            TagName::Progress => TagName::Progress,
             TagName::R => TagName::R,
             TagName::S => TagName::S,
             TagName::Span => TagName::Span,
+            TagName::Table => TagName::Table,
+            TagName::Td => TagName::Td,
+            TagName::Th => TagName::Th,
+            TagName::Tr => TagName::Tr,
             TagName::U => TagName::U,
+            TagName::Ul => TagName::Ul,
             TagName::X => TagName::X,
             TagName::Ziyy => TagName::Ziyy,
             TagName::Root => TagName::Root,
@@ -524,3 +630,34 @@ impl<I: ?Sized + Input> Clone for TagName<'_, I> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_extended_colors_from_ansi() {
+        let style = Tag::parse_from_ansi("38;5;196;48;2;1;2;3;58;5;4", Span::inserted()).style;
+        assert_eq!(style.fg_color(), Color::Ansi256(Ansi256(196)));
+        assert_eq!(style.bg_color(), Color::Rgb(Rgb(1, 2, 3)));
+        assert_eq!(style.ul_color(), Color::Ansi256(Ansi256(4)));
+    }
+
+    #[test]
+    fn parse_extended_color_followed_by_parameters() {
+        let style = Tag::parse_from_ansi("48;5;21;1", Span::inserted()).style;
+        assert_eq!(style.bg_color(), Color::Ansi256(Ansi256(21)));
+        assert_eq!(style.intensity(), Intensity::Bold);
+    }
+
+    #[test]
+    fn parse_reset_from_ansi() {
+        for params in ["", "0"] {
+            let style = Tag::parse_from_ansi(params, Span::inserted()).style;
+            assert_eq!(style.reset(), Reset::Yes);
+        }
+
+        let style = Tag::parse_from_ansi("1", Span::inserted()).style;
+        assert_eq!(style.reset(), Reset::No);
+    }
+}
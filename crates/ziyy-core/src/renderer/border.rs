@@ -0,0 +1,83 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::parser::Tag;
+use crate::shared::{Input, Value};
+
+/// Characters a border is drawn with.
+pub(super) struct Border {
+    /// Horizontal line.
+    pub(super) h: &'static str,
+    /// Vertical line.
+    pub(super) v: &'static str,
+    /// Corners and junctions, from top to bottom and from left to right.
+    pub(super) joints: [[&'static str; 3]; 3],
+}
+
+const SINGLE: Border = Border {
+    h: "─",
+    v: "│",
+    joints: [["┌", "┬", "┐"], ["├", "┼", "┤"], ["└", "┴", "┘"]],
+};
+
+const DOUBLE: Border = Border {
+    h: "═",
+    v: "║",
+    joints: [["╔", "╦", "╗"], ["╠", "╬", "╣"], ["╚", "╩", "╝"]],
+};
+
+pub(super) const ROUNDED: Border = Border {
+    h: "─",
+    v: "│",
+    joints: [["╭", "┬", "╮"], ["├", "┼", "┤"], ["╰", "┴", "╯"]],
+};
+
+const HEAVY: Border = Border {
+    h: "━",
+    v: "┃",
+    joints: [["┏", "┳", "┓"], ["┣", "╋", "┫"], ["┗", "┻", "┛"]],
+};
+
+const ASCII: Border = Border {
+    h: "-",
+    v: "|",
+    joints: [["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"]],
+};
+
+impl Border {
+    /// Reads a `border` attribute stored in `value`. A bare `border` is a single line.
+    pub(super) fn parse<'src, I: ?Sized + Input>(
+        value: &Value<'src, I>,
+        tag: &Tag<'src, I>,
+    ) -> Result<'src, I, Option<&'static Self>> {
+        match *value {
+            Value::None => Ok(None),
+            Value::Bool => Ok(Some(&SINGLE)),
+            Value::Some(val) => match val.as_ref() {
+                b"single" => Ok(Some(&SINGLE)),
+                b"double" => Ok(Some(&DOUBLE)),
+                b"rounded" => Ok(Some(&ROUNDED)),
+                b"heavy" => Ok(Some(&HEAVY)),
+                b"ascii" => Ok(Some(&ASCII)),
+                _ => Err(Error {
+                    kind: ErrorKind::InvalidBorder(val),
+                    span: tag.spans.custom,
+                }),
+            },
+        }
+    }
+
+    /// Returns a horizontal line of the border, over columns of `widths`.
+    ///
+    /// `row` picks the joints: 0 for the top line, 1 between rows and 2 for the bottom line.
+    pub(super) fn line(&self, row: usize, widths: &[usize]) -> String {
+        let [left, mid, right] = self.joints[row];
+        let mut line = String::from(left);
+        for (i, width) in widths.iter().enumerate() {
+            if i > 0 {
+                line.push_str(mid);
+            }
+            line.push_str(&self.h.repeat(*width));
+        }
+        line.push_str(right);
+        line
+    }
+}
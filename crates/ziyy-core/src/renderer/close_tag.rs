@@ -1,5 +1,3 @@
-use std::ops::Not;
-
 use crate::context::Context;
 use crate::error::Result;
 use crate::parser::{Tag, TagName};
@@ -13,10 +11,34 @@ impl<O> Renderer<O> {
         ctx: &mut Context<'src, I>,
         tag: &Tag<'src, I>,
     ) -> Result<'src, I, ()> {
+        let from = ctx.state.current();
         let diff = ctx.state.pop_tag(tag)?;
 
-        self.buf
-            .extend_from_slice(&diff.not().to_string2().as_bytes());
+        if tag.name == TagName::Box {
+            // whitespace before the end of a box would widen it
+            self.trim_break();
+        }
+        self.write_style(diff, from, ctx.state.current());
+
+        match tag.name {
+            TagName::Div
+            | TagName::P
+            | TagName::Pre
+            | TagName::H1
+            | TagName::H2
+            | TagName::H3
+            | TagName::Box
+            | TagName::Ziyy => {
+                self.close_block();
+            }
+            TagName::Table => self.close_table(),
+            TagName::Gradient => self.close_gradient(ctx.state.current()),
+            TagName::Ul | TagName::Ol => {
+                self.lists.pop();
+            }
+            TagName::Td | TagName::Th => self.close_cell(tag.name == TagName::Th),
+            _ => {}
+        }
 
         if tag.name == TagName::Pre {
             self.pre_ws -= 1;
@@ -0,0 +1,133 @@
+use crate::error::Error;
+use crate::parser::Tag;
+use crate::shared::{Input, Value};
+use crate::style::Color;
+
+use super::border::{Border, ROUNDED};
+use super::layout::Block;
+use super::wrap::display_width;
+use super::{Renderer, attr_or};
+
+/// The border drawn around a `<box>`.
+pub(super) struct Frame {
+    border: &'static Border,
+    /// Color of the border, `Color::None` to draw it in the style of the box.
+    color: Color,
+    /// Title written in the top line of the border.
+    title: String,
+}
+
+impl Frame {
+    /// Reads the `border`, `border-color` and `title` attributes of a box. Boxes have a
+    /// rounded border by default.
+    ///
+    /// Invalid attributes keep their default and the first error is kept in `error`.
+    pub(super) fn parse<'src, I: ?Sized + Input>(
+        tag: &Tag<'src, I>,
+        error: &mut Option<Error<'src, I>>,
+    ) -> Self {
+        let color = match tag.border_color {
+            Value::Some(val) => attr_or(
+                Color::parse(val, tag.spans.border_color),
+                Color::None,
+                error,
+            ),
+            _ => Color::None,
+        };
+        let title = match tag.title {
+            Value::Some(val) => String::from_utf8_lossy(val.as_ref()).into_owned(),
+            _ => String::new(),
+        };
+
+        Frame {
+            border: attr_or(Border::parse(&tag.custom, tag), None, error).unwrap_or(&ROUNDED),
+            color,
+            title,
+        }
+    }
+}
+
+impl<O> Renderer<O> {
+    /// Draws the border of a box around its lines.
+    ///
+    /// The box is as wide as its widest line, or as its `width` if that is wider. Each line
+    /// is written in the style it was rendered with and the style of the box is restored
+    /// before the right border.
+    pub(super) fn close_frame(&mut self, block: &Block, frame: &Frame) {
+        let content: Vec<u8> = self.buf.drain(block.start..).collect();
+        let mut lines: Vec<&[u8]> = content.split(|b| *b == b'\n').collect();
+        // the last line only holds escape sequences when the content ends with a newline
+        if lines.len() > 1 && lines.last().is_some_and(|line| self.width(line) == 0) {
+            lines.pop();
+        }
+
+        let widest = lines.iter().map(|line| self.width(line)).max();
+        let mut width = block
+            .content_width(None)
+            .unwrap_or_default()
+            .max(widest.unwrap_or_default());
+        let title = display_width(frame.title.as_bytes());
+        if title > 0 {
+            // the title is written between spaces, after a piece of the top line
+            width = width.max((title + 4).saturating_sub(2 * block.pad));
+        }
+        let inner = width + 2 * block.pad;
+
+        let mut border = block.style;
+        if frame.color != Color::None {
+            border.set_fg_color(frame.color);
+        }
+        let Border { h, v, joints } = frame.border;
+        let [[top_left, _, top_right], _, [bottom_left, _, bottom_right]] = *joints;
+
+        self.write_spaces(block.margin);
+        self.write_revert(border, block.parent);
+        self.buf.extend_from_slice(top_left.as_bytes());
+        if title > 0 {
+            self.buf.extend_from_slice(h.as_bytes());
+            self.buf.push(b' ');
+            self.write_text(frame.title.as_bytes());
+            self.buf.push(b' ');
+            self.buf
+                .extend_from_slice(h.repeat(inner - title - 3).as_bytes());
+        } else {
+            self.buf.extend_from_slice(h.repeat(inner).as_bytes());
+        }
+        self.buf.extend_from_slice(top_right.as_bytes());
+        self.write_revert(block.parent, border);
+        self.buf.push(b'\n');
+
+        // the style of the terminal at the start of a line
+        let mut style = block.parent;
+        for line in lines {
+            let end = self.style_after(style, line);
+            let space = width - self.width(line);
+            let left = block.align.offset(space);
+
+            self.write_spaces(block.margin);
+            self.write_revert(border, block.parent);
+            self.buf.extend_from_slice(v.as_bytes());
+            self.write_revert(block.style, border);
+            self.write_spaces(block.pad + left);
+            self.write_revert(style, block.style);
+            self.buf.extend_from_slice(line);
+            self.write_revert(block.style, end);
+            self.write_spaces(space - left + block.pad);
+            self.write_revert(border, block.style);
+            self.buf.extend_from_slice(v.as_bytes());
+            self.write_revert(block.parent, border);
+            self.buf.push(b'\n');
+
+            style = end;
+        }
+
+        self.write_spaces(block.margin);
+        self.write_revert(border, block.parent);
+        self.buf.extend_from_slice(bottom_left.as_bytes());
+        self.buf.extend_from_slice(h.repeat(inner).as_bytes());
+        self.buf.extend_from_slice(bottom_right.as_bytes());
+        self.write_revert(block.parent, border);
+
+        self.wrap_at = None;
+    }
+}
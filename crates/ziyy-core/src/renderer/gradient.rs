@@ -0,0 +1,204 @@
+use std::iter;
+
+use crate::ansi::{Segment, apply_sgr};
+use crate::error::{Error, ErrorKind, Result};
+use crate::parser::Tag;
+use crate::shared::{Input, Span, Value};
+use crate::style::{Color, Rgb, Style};
+
+use super::{Format, Renderer, attr_or};
+
+/// An open `<gradient>` element.
+pub(super) struct Gradient {
+    /// Start of the content of the gradient in the buffer.
+    start: usize,
+    /// Style of the gradient.
+    style: Style,
+    /// Colors the gradient goes through, at equal distances.
+    stops: Vec<Rgb>,
+    /// Flag to indicate whether the gradient colors the background instead of the text.
+    bg: bool,
+}
+
+impl Gradient {
+    /// Returns the color of the character at `index` of `count` characters.
+    fn color(&self, index: usize, count: usize) -> Rgb {
+        let segments = self.stops.len() - 1;
+        if segments == 0 || count < 2 {
+            return self.stops[0];
+        }
+
+        // the character is `pos / last` stops away from the first one
+        let (pos, last) = (index * segments, count - 1);
+        let stop = (pos / last).min(segments - 1);
+        let offset = pos - stop * last;
+        let (from, to) = (self.stops[stop], self.stops[stop + 1]);
+        let mix = |from: u8, to: u8| {
+            let (from, to) = (usize::from(from), usize::from(to));
+            u8::try_from((from * (last - offset) + to * offset + last / 2) / last)
+                .unwrap_or(u8::MAX)
+        };
+        Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+}
+
+/// Reads a color of a gradient, written in the attribute at `span`.
+fn parse_stop<I: ?Sized + Input>(source: &I, span: Span) -> Result<'_, I, Rgb> {
+    Color::parse(source, span)?.to_rgb().ok_or(Error {
+        kind: ErrorKind::InvalidColor(source),
+        span,
+    })
+}
+
+impl<O> Renderer<O> {
+    /// Opens a gradient, reading its `from`, `stops` and `to` colors. `start` is where its
+    /// content starts in the buffer and `style` is the style of the gradient.
+    pub(super) fn open_gradient<'src, I: ?Sized + Input>(
+        &mut self,
+        tag: &Tag<'src, I>,
+        start: usize,
+        style: Style,
+    ) -> Result<'src, I, ()> {
+        let mut error = None;
+        let mut stops = Vec::new();
+        // invalid colors are left out
+        let mut read_stop =
+            |source, span| attr_or(parse_stop(source, span).map(Some), None, &mut error);
+        if let Value::Some(from) = tag.from {
+            stops.extend(read_stop(from, tag.spans.from));
+        }
+        if let Value::Some(list) = tag.stops {
+            // colors are separated by commas outside of parentheses
+            let bytes = list.as_ref();
+            let (mut depth, mut begin) = (0usize, 0);
+            for (end, b) in bytes.iter().chain([&b',']).enumerate() {
+                match b {
+                    b'(' => depth += 1,
+                    b')' => depth = depth.saturating_sub(1),
+                    b',' if depth == 0 => {
+                        let stop = &bytes[begin..end];
+                        let lead = stop.len() - stop.trim_ascii_start().len();
+                        let len = stop.trim_ascii().len();
+                        if len > 0 {
+                            let from = begin + lead;
+                            stops.extend(read_stop(&list[from..from + len], tag.spans.stops));
+                        }
+                        begin = end + 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Value::Some(to) = tag.to {
+            stops.extend(read_stop(to, tag.spans.to));
+        }
+
+        self.gradients.push(Gradient {
+            start,
+            style,
+            stops,
+            bg: !matches!(tag.custom, Value::None),
+        });
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Closes the innermost gradient, coloring each character of its content. `style` is
+    /// the style of the terminal after the gradient.
+    ///
+    /// Colors are downsampled like other colors, and characters of the same color share an
+    /// escape sequence. A gradient without colors keeps the colors of its content.
+    pub(super) fn close_gradient(&mut self, style: Style) {
+        let Some(gradient) = self.gradients.pop() else {
+            return;
+        };
+        if !self.styled || gradient.stops.is_empty() {
+            return;
+        }
+
+        let content: Vec<u8> = self.buf.drain(gradient.start..).collect();
+        // segments are collected with their end, so the buffer can be written while reading them
+        let segments: Vec<(Segment, usize)> = {
+            let mut segments = self.segments(&content);
+            iter::from_fn(|| Some((segments.next()?, segments.offset()))).collect()
+        };
+        let chars = |text: &[u8]| {
+            String::from_utf8_lossy(text)
+                .chars()
+                .filter(|c| *c != '\n')
+                .count()
+        };
+        let count = segments
+            .iter()
+            .map(|(segment, _)| match segment {
+                Segment::Text(text) => chars(text),
+                _ => 0,
+            })
+            .sum();
+
+        let (mut index, mut last) = (0, String::new());
+        // the style of the terminal with the last color of the gradient
+        let mut colored = None;
+        // the style of the content, and the style of the output
+        let (mut current, mut written) = (gradient.style, gradient.style);
+        let mut start = 0;
+        for (segment, end) in segments {
+            let Segment::Text(text) = segment else {
+                match segment {
+                    // spans of the content are replaced with spans of the colors
+                    Segment::Sgr(sgr) if self.format == Format::Html => {
+                        current = apply_sgr(current, sgr);
+                    }
+                    _ => self.buf.extend_from_slice(&content[start..end]),
+                }
+                // the sequence may have changed the color
+                last.clear();
+                start = end;
+                continue;
+            };
+            start = end;
+
+            for c in String::from_utf8_lossy(text).chars() {
+                if c == '\n' {
+                    // colors do not reach past the end of a line
+                    if let Some(colored) = colored.take() {
+                        self.write_revert(style, colored);
+                        written = style;
+                    }
+                    last.clear();
+                } else {
+                    let color = Color::Rgb(gradient.color(index, count));
+                    // HTML spans set the whole style of the character
+                    let (mut diff, mut next) = match self.format {
+                        Format::Html => (Style::new(), current),
+                        _ => (Style::new(), style),
+                    };
+                    if gradient.bg {
+                        diff.set_bg_color(color);
+                        next.set_bg_color(color);
+                    } else {
+                        diff.set_fg_color(color);
+                        next.set_fg_color(color);
+                    }
+                    colored = Some(next);
+
+                    // spans are only written when their CSS changes
+                    let sgr = self.sgr(diff);
+                    if sgr != last || self.format == Format::Html {
+                        self.write_style(diff, written, next);
+                        written = next;
+                        last = sgr;
+                    }
+                    index += 1;
+                }
+
+                let mut bytes = [0; 4];
+                self.write_text(c.encode_utf8(&mut bytes).as_bytes());
+            }
+        }
+
+        if let Some(colored) = colored {
+            self.write_revert(style, colored);
+        }
+        self.wrap_at = None;
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use smallvec::SmallVec;
+
+use crate::ansi::{self, Segment};
+use crate::style::{
+    Blink, Delete, FontStyle, Hide, Intensity, Invert, Reset, Rgb, Style, Underline,
+};
+#[cfg(feature = "uncommon")]
+use crate::style::{Frame, Overline};
+
+use super::Renderer;
+
+impl<O> Renderer<O> {
+    /// Writes the `<span>` elements of a change from the style `from` to the style `to`.
+    ///
+    /// Spans are never nested, the span of `from` is closed and a span of `to` is opened
+    /// whenever the CSS of the style changes.
+    pub(super) fn write_span(&mut self, from: Style, to: Style) {
+        let (open, css) = (css(&from), css(&to));
+        if open == css {
+            return;
+        }
+
+        if !open.is_empty() {
+            self.buf.extend_from_slice(b"</span>");
+        }
+        if !css.is_empty() {
+            self.buf.extend_from_slice(b"<span style=\"");
+            self.buf.extend_from_slice(css.as_bytes());
+            self.buf.extend_from_slice(b"\">");
+            self.html_styles.entry(css).or_insert(to);
+        }
+    }
+}
+
+/// An iterator over the [`Segment`]s of rendered HTML.
+///
+/// `<span>` elements become [`Segment::Sgr`]s setting the style of the span, `<a>` elements
+/// become [`Segment::Link`]s and character references become the text they stand for.
+pub(super) struct Segments<'a, 's> {
+    source: &'a [u8],
+    current: usize,
+    /// Styles of the written spans, by their CSS.
+    styles: &'s HashMap<String, Style>,
+}
+
+impl<'a, 's> Segments<'a, 's> {
+    pub(super) fn new(source: &'a [u8], styles: &'s HashMap<String, Style>) -> Self {
+        Segments {
+            source,
+            current: 0,
+            styles,
+        }
+    }
+
+    /// Returns the offset in the source after the last returned segment.
+    pub(super) fn offset(&self) -> usize {
+        self.current
+    }
+
+    /// Returns the segment of an element.
+    fn element(&self, element: &'a [u8]) -> Segment<'a> {
+        let value = |prefix: &[u8]| {
+            element
+                .strip_prefix(prefix)
+                .and_then(|value| value.strip_suffix(b"\">"))
+        };
+
+        if let Some(href) = value(b"<a href=\"") {
+            return Segment::Link(href);
+        } else if element == b"</a>" {
+            return Segment::Link(b"");
+        }
+
+        let mut sgr = match value(b"<span style=\"") {
+            Some(css) => str::from_utf8(css)
+                .ok()
+                .and_then(|css| self.styles.get(css).copied())
+                .unwrap_or_else(Style::new),
+            None if element == b"</span>" => Style::new(),
+            None => return Segment::Other(element),
+        };
+        // a span sets the whole style
+        sgr.set_reset(Reset::Yes);
+        Segment::Sgr(sgr)
+    }
+}
+
+impl<'a> Iterator for Segments<'a, '_> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.source[self.current..];
+        let end = match rest.first()? {
+            b'<' => rest
+                .iter()
+                .position(|b| *b == b'>')
+                .map_or(rest.len(), |n| n + 1),
+            b'&' => rest
+                .iter()
+                .position(|b| *b == b';')
+                .map_or(rest.len(), |n| n + 1),
+            _ => rest
+                .iter()
+                .position(|b| matches!(b, b'<' | b'&'))
+                .unwrap_or(rest.len()),
+        };
+        self.current += end;
+
+        let segment = &rest[..end];
+        Some(match segment {
+            [b'<', ..] => self.element(segment),
+            b"&amp;" => Segment::Text(b"&"),
+            b"&lt;" => Segment::Text(b"<"),
+            b"&gt;" => Segment::Text(b">"),
+            b"&quot;" => Segment::Text(b"\""),
+            b"&#39;" => Segment::Text(b"'"),
+            _ => Segment::Text(segment),
+        })
+    }
+}
+
+/// Writes `text` escaped for HTML, dropping escape sequences and control characters other
+/// than newlines and tabs.
+pub(super) fn escape(text: &[u8], out: &mut SmallVec<[u8; 1024]>) {
+    for segment in ansi::Segments::new(text) {
+        let Segment::Text(text) = segment else {
+            continue;
+        };
+        for b in text {
+            match b {
+                b'&' => out.extend_from_slice(b"&amp;"),
+                b'<' => out.extend_from_slice(b"&lt;"),
+                b'>' => out.extend_from_slice(b"&gt;"),
+                b'"' => out.extend_from_slice(b"&quot;"),
+                b'\'' => out.extend_from_slice(b"&#39;"),
+                b'\n' | b'\t' => out.push(*b),
+                b if b.is_ascii_control() => {}
+                _ => out.push(*b),
+            }
+        }
+    }
+}
+
+fn hex(Rgb(r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Returns the CSS declarations of a style.
+fn css(style: &Style) -> String {
+    let mut css = String::new();
+
+    match style.intensity() {
+        Intensity::Bold => css.push_str("font-weight:bold;"),
+        Intensity::Dim => css.push_str("opacity:0.5;"),
+        _ => {}
+    }
+
+    match style.font_style() {
+        FontStyle::Italics => css.push_str("font-style:italic;"),
+        FontStyle::Fraktur => css.push_str("font-family:fantasy;"),
+        _ => {}
+    }
+
+    let fg = style.fg_color().to_rgb().map(hex);
+    let bg = style.bg_color().to_rgb().map(hex);
+    let (fg, bg) = if style.invert() == Invert::Set {
+        (
+            Some(bg.unwrap_or_else(|| "Canvas".to_string())),
+            Some(fg.unwrap_or_else(|| "CanvasText".to_string())),
+        )
+    } else {
+        (fg, bg)
+    };
+
+    if let Some(fg) = fg {
+        let _ = write!(css, "color:{fg};");
+    }
+    if let Some(bg) = bg {
+        let _ = write!(css, "background-color:{bg};");
+    }
+
+    let mut lines = Vec::new();
+    let underline = style.underline();
+    if !matches!(underline, Underline::None | Underline::Unset) {
+        lines.push("underline");
+    }
+    #[cfg(feature = "uncommon")]
+    if style.overline() == Overline::Set {
+        lines.push("overline");
+    }
+    if style.delete() == Delete::Set {
+        lines.push("line-through");
+    }
+    if matches!(style.blink(), Blink::Slow | Blink::Fast) {
+        lines.push("blink");
+    }
+
+    if !lines.is_empty() {
+        let _ = write!(css, "text-decoration-line:{};", lines.join(" "));
+    }
+
+    match underline {
+        Underline::Double => css.push_str("text-decoration-style:double;"),
+        Underline::Curly => css.push_str("text-decoration-style:wavy;"),
+        Underline::Dotted => css.push_str("text-decoration-style:dotted;"),
+        Underline::Dashed => css.push_str("text-decoration-style:dashed;"),
+        _ => {}
+    }
+
+    if let Some(ul) = style.ul_color().to_rgb() {
+        let _ = write!(css, "text-decoration-color:{};", hex(ul));
+    }
+
+    if style.hide() == Hide::Set {
+        css.push_str("visibility:hidden;");
+    }
+
+    #[cfg(feature = "uncommon")]
+    match style.frame() {
+        Frame::Framed => css.push_str("border:1px solid;"),
+        Frame::Encircled => css.push_str("border:1px solid;border-radius:50%;"),
+        _ => {}
+    }
+
+    css.pop();
+
+    css
+}
@@ -0,0 +1,210 @@
+use crate::ansi::{Segment, apply_sgr};
+use crate::context::Context;
+use crate::error::{Error, ErrorKind, Result};
+use crate::num::input_to_size;
+use crate::parser::{Tag, TagName};
+use crate::shared::{Input, Value};
+use crate::style::Style;
+
+use super::frame::Frame;
+use super::{Renderer, attr_or};
+
+/// Alignment of the lines of a block or a cell.
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    /// Reads the `align` attribute of a tag.
+    pub(super) fn parse<'src, I: ?Sized + Input>(tag: &Tag<'src, I>) -> Result<'src, I, Self> {
+        match tag.align {
+            Value::Some(val) => match val.as_ref() {
+                b"left" => Ok(Align::Left),
+                b"center" => Ok(Align::Center),
+                b"right" => Ok(Align::Right),
+                _ => Err(Error {
+                    kind: ErrorKind::InvalidAlign(val),
+                    span: tag.spans.align,
+                }),
+            },
+            _ => Ok(Align::Left),
+        }
+    }
+
+    /// Returns the spaces to write before a line, given the `space` left in its width.
+    pub(super) fn offset(self, space: usize) -> usize {
+        match self {
+            Align::Left => 0,
+            Align::Center => space / 2,
+            Align::Right => space,
+        }
+    }
+}
+
+/// An open block element.
+pub(super) struct Block {
+    /// Width set with the `width` attribute.
+    width: Option<usize>,
+    /// Flag to indicate whether the block is in a `<pre>` element.
+    pub(super) pre: bool,
+    pub(super) align: Align,
+    /// Spaces added inside both sides of each line.
+    pub(super) pad: usize,
+    /// Spaces added outside both sides of each line.
+    pub(super) margin: usize,
+    /// Border drawn around a `<box>`.
+    frame: Option<Frame>,
+    /// Start of the block in the buffer.
+    pub(super) start: usize,
+    /// Style of the parent of the block.
+    pub(super) parent: Style,
+    /// Style of the block.
+    pub(super) style: Style,
+}
+
+impl Block {
+    /// Returns the width of the content of the block in a parent of `width` columns.
+    pub(super) fn content_width(&self, width: Option<usize>) -> Option<usize> {
+        let width = self.width.or(width)?;
+        let border = usize::from(self.frame.is_some());
+        Some(width.saturating_sub(2 * (self.pad + self.margin + border)))
+    }
+}
+
+impl<O> Renderer<O> {
+    /// Opens a block element, reading its layout attributes.
+    ///
+    /// `start` is where the block starts in the buffer and `parent` is the style of its
+    /// parent. The style of the block must already be pushed. The block is opened even if
+    /// an attribute is invalid, invalid attributes keep their default.
+    pub(super) fn open_block<'src, I: ?Sized + Input>(
+        &mut self,
+        ctx: &Context<'src, I>,
+        tag: &Tag<'src, I>,
+        start: usize,
+        parent: Style,
+    ) -> Result<'src, I, ()> {
+        let number = |value: &Value<'src, I>, span| -> Result<'src, I, Option<usize>> {
+            match *value {
+                Value::Some(val) => Ok(Some(get_num2!(input_to_size(val), span))),
+                _ => Ok(None),
+            }
+        };
+
+        // <ziyy> normalizes whitespace again
+        let pre = match tag.name {
+            TagName::Pre => true,
+            TagName::Ziyy => false,
+            _ => self.blocks.last().is_some_and(|block| block.pre),
+        };
+
+        let mut error = None;
+        self.blocks.push(Block {
+            width: attr_or(number(&tag.width, tag.spans.width), None, &mut error),
+            pre,
+            align: attr_or(Align::parse(tag), Align::Left, &mut error),
+            // the content of a box does not touch its border by default
+            pad: attr_or(number(&tag.pad, tag.spans.pad), None, &mut error)
+                .unwrap_or(usize::from(tag.name == TagName::Box)),
+            margin: attr_or(number(&tag.margin, tag.spans.margin), None, &mut error)
+                .unwrap_or_default(),
+            frame: match tag.name {
+                TagName::Box => Some(Frame::parse(tag, &mut error)),
+                _ => None,
+            },
+            start,
+            parent,
+            style: ctx.state.current(),
+        });
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Returns the width of the content of the open blocks in an output of `width` columns.
+    pub(super) fn content_width(&self, width: Option<usize>) -> Option<usize> {
+        self.blocks
+            .iter()
+            .fold(width, |width, block| block.content_width(width))
+    }
+
+    /// Closes the innermost block element, aligning and padding its lines, or drawing the
+    /// border of a box around them.
+    ///
+    /// Lines are aligned in the width of the block, or in the width of its widest line if
+    /// neither the block nor the renderer has a width.
+    pub(super) fn close_block(&mut self) {
+        let Some(block) = self.blocks.pop() else {
+            return;
+        };
+        if let Some(frame) = &block.frame {
+            self.close_frame(&block, frame);
+            return;
+        }
+        if block.align == Align::Left && block.pad == 0 && block.margin == 0 {
+            return;
+        }
+
+        let content: Vec<u8> = self.buf.drain(block.start..).collect();
+        let width = block
+            .content_width(self.content_width(self.width))
+            .or_else(|| {
+                content
+                    .split(|b| *b == b'\n')
+                    .map(|line| self.width(line))
+                    .max()
+            });
+        let lines = content.split(|b| *b == b'\n').count();
+        // the style of the terminal at the start of a line
+        let mut style = block.parent;
+
+        for (i, line) in content.split(|b| *b == b'\n').enumerate() {
+            if i > 0 {
+                self.buf.push(b'\n');
+            }
+
+            let end = self.style_after(style, line);
+            let used = self.width(line);
+            if i == lines - 1 && used == 0 {
+                self.buf.extend_from_slice(line);
+                break;
+            }
+
+            let space = width.map_or(0, |width| width.saturating_sub(used));
+            let left = block.align.offset(space);
+
+            self.write_spaces(block.margin);
+            self.write_revert(block.style, style);
+            self.write_spaces(block.pad + left);
+            self.write_revert(style, block.style);
+            self.buf.extend_from_slice(line);
+            if block.pad > 0 {
+                self.write_revert(block.style, end);
+                self.write_spaces(space - left + block.pad);
+                self.write_revert(end, block.style);
+            }
+
+            style = end;
+        }
+
+        self.wrap_at = None;
+    }
+
+    /// Returns the style of the output after `text` written by the renderer follows
+    /// `style`.
+    pub(super) fn style_after(&self, style: Style, text: &[u8]) -> Style {
+        let style = self
+            .segments(text)
+            .fold(style, |style, segment| match segment {
+                Segment::Sgr(sgr) => apply_sgr(style, sgr),
+                _ => style,
+            });
+        // unset attributes are dropped by adding the style to an empty one
+        Style::new() + style
+    }
+
+    pub(super) fn write_spaces(&mut self, n: usize) {
+        self.buf.extend(std::iter::repeat_n(b' ', n));
+    }
+}
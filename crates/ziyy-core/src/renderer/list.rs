@@ -0,0 +1,87 @@
+use crate::error::{Error, Result};
+use crate::num::input_to_u32;
+use crate::parser::{Tag, TagName};
+use crate::shared::{Input, Value};
+
+use super::wrap::display_width;
+use super::{Renderer, attr_or};
+
+/// Bullets of unordered lists, by depth.
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// An open list.
+pub(super) struct List {
+    /// Column the markers of the items start at.
+    indent: usize,
+    /// Number of the next item, `None` for unordered lists. `start` is a `u32`, so the
+    /// number of items can not make it overflow.
+    next: Option<u64>,
+    bullet: String,
+    /// Column wrapped lines of the current item start at.
+    hang: usize,
+}
+
+impl<O> Renderer<O> {
+    /// Opens a `<ul>` or `<ol>` list, reading its `bullet` or `start` attribute.
+    ///
+    /// An `<ol>` with an invalid `start` is still opened and starts at 1.
+    pub(super) fn open_list<'src, I: ?Sized + Input>(
+        &mut self,
+        tag: &Tag<'src, I>,
+    ) -> Result<'src, I, ()> {
+        let mut error = None;
+        let (next, bullet) = match (&tag.name, &tag.custom) {
+            (TagName::Ol, &Value::Some(val)) => {
+                let start = input_to_u32(val, 10).map_err(|kind| Error {
+                    kind,
+                    span: tag.spans.custom,
+                });
+                (Some(u64::from(attr_or(start, 1, &mut error))), "")
+            }
+            (TagName::Ol, _) => (Some(1), ""),
+            (_, &Value::Some(val)) => (None, str::from_utf8(val.as_ref()).unwrap_or_default()),
+            _ => (None, BULLETS[self.lists.len() % BULLETS.len()]),
+        };
+
+        // nested lists start at the text of the item they are in
+        let indent = self.hang();
+        self.lists.push(List {
+            indent,
+            next,
+            bullet: bullet.to_string(),
+            hang: indent,
+        });
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Writes the marker of a list item, a bullet or the number of the item.
+    pub(super) fn open_item(&mut self) {
+        let (indent, marker) = match self.lists.last_mut() {
+            Some(list) => {
+                let marker = match &mut list.next {
+                    Some(n) => {
+                        let marker = format!("{n}.");
+                        *n += 1;
+                        marker
+                    }
+                    None => list.bullet.clone(),
+                };
+                (list.indent, marker)
+            }
+            None => (0, BULLETS[0].to_string()),
+        };
+
+        self.write_spaces(indent);
+        self.write_text(marker.as_bytes());
+        self.buf.push(b' ');
+
+        if let Some(list) = self.lists.last_mut() {
+            list.hang = indent + display_width(marker.as_bytes()) + 1;
+        }
+    }
+
+    /// Returns the column wrapped lines start at.
+    pub(super) fn hang(&self) -> usize {
+        self.lists.last().map_or(0, |list| list.hang)
+    }
+}
@@ -1,22 +1,121 @@
+use std::collections::HashMap;
+use std::env;
 use std::fmt;
-use std::io;
-use std::ops::Not;
+use std::io::{self, IsTerminal};
+#[cfg(feature = "bindings")]
+use std::sync::Arc;
 
 use smallvec::{SmallVec, smallvec};
+use terminal_size::{Width, terminal_size};
 
-use crate::context::Context;
-use crate::error::Result;
-use crate::parser::{Chunk, Parser, TagKind, TagName};
+use crate::context::{Context, revert};
+use crate::error::{Error, ErrorKind, Result};
+use crate::parser::{Chunk, Parser, Tag, TagKind, TagName};
 use crate::scanner::is_whitespace;
 pub use crate::shared::Input;
-use crate::style::Style;
+use crate::style::{Color, ColorDepth, Style};
+#[cfg(feature = "bindings")]
+use crate::stylesheet::Stylesheet;
 #[cfg(feature = "tree")]
 use crate::tree::Tree;
 
+mod border;
 mod close_tag;
+mod frame;
+mod gradient;
+mod html;
+mod layout;
+mod list;
 mod open_tag;
+mod progress;
 mod self_close_tag;
+mod table;
+mod template;
+mod wrap;
 
+/// Format of the output written by a [`Renderer`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Styles and links are written as ANSI escape sequences.
+    #[default]
+    Ansi,
+    /// Styles and links are dropped, only the laid out text is written.
+    Plain,
+    /// Styles are written as `<span style="...">` elements and links as `<a href="...">`
+    /// elements. Text is escaped, escape sequences other than styles are dropped and
+    /// whitespace is kept as is, so the output is meant to be placed inside a `<pre>`
+    /// element.
+    Html,
+}
+
+/// When a [`Renderer`] writes styles.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Styles are always written.
+    #[default]
+    Always,
+    /// Styles are written when the output is a terminal and `NO_COLOR` is not set. Setting
+    /// `CLICOLOR_FORCE` forces styles.
+    Auto,
+    /// Styles are never written.
+    Never,
+}
+
+impl Policy {
+    /// Returns whether styles should be written to `stream`.
+    ///
+    /// With [`Policy::Auto`], styles are written when `CLICOLOR_FORCE` is set to a value other
+    /// than `0`, or when `NO_COLOR` is not set to a non-empty value and `stream` is a terminal.
+    #[must_use]
+    pub fn should_style(self, stream: &impl IsTerminal) -> bool {
+        self.resolve(stream.is_terminal())
+    }
+
+    /// Returns whether styles should be written to an output that is a terminal or not.
+    fn resolve(self, terminal: bool) -> bool {
+        match self {
+            Policy::Always => true,
+            Policy::Never => false,
+            Policy::Auto => {
+                if env::var_os("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+                    true
+                } else if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+                    false
+                } else {
+                    terminal
+                }
+            }
+        }
+    }
+}
+
+/// Returns the number of columns of the terminal.
+///
+/// The width of the standard output is used, then `COLUMNS`. Returns `None` when neither is
+/// known.
+#[must_use]
+pub fn terminal_width() -> Option<usize> {
+    terminal_size()
+        .map(|(Width(width), _)| usize::from(width))
+        .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+}
+
+/// Returns the value of an attribute, or `default` if it is invalid.
+///
+/// The first error is kept in `error`, so the element can still be opened and its close tag
+/// matches when rendering recovers from the error.
+fn attr_or<'src, I: ?Sized + Input, T>(
+    value: Result<'src, I, T>,
+    default: T,
+    error: &mut Option<Error<'src, I>>,
+) -> T {
+    value.unwrap_or_else(|e| {
+        error.get_or_insert(e);
+        default
+    })
+}
+
+#[allow(clippy::struct_excessive_bools)]
 pub struct Renderer<O> {
     /// A buffer to store the parsed output.
     buf: SmallVec<[u8; 1024]>,
@@ -28,6 +127,35 @@ pub struct Renderer<O> {
     pre_ws: i16,
     /// The last written printable element.
     block_start: bool,
+    /// Format of the output.
+    format: Format,
+    /// Flag to indicate whether only escape sequences are interpreted.
+    escapes_only: bool,
+    /// Colors are downsampled to this depth.
+    color_depth: ColorDepth,
+    /// When styles are written.
+    policy: Policy,
+    /// Flag to indicate whether the output is a terminal, for [`Policy::Auto`].
+    terminal: bool,
+    /// Flag to indicate whether styles are written, resolved from the format and policy.
+    styled: bool,
+    /// Width lines are wrapped at.
+    width: Option<usize>,
+    /// Open block elements, innermost last.
+    blocks: Vec<layout::Block>,
+    /// Open gradients, innermost last.
+    gradients: Vec<gradient::Gradient>,
+    /// Open lists, innermost last.
+    lists: Vec<list::List>,
+    /// Open tables, innermost last.
+    tables: Vec<table::Table>,
+    /// The last whitespace the current line can be broken at.
+    wrap_at: Option<wrap::Break>,
+    /// Styles of the `<span>` elements written in HTML, by their CSS.
+    html_styles: HashMap<String, Style>,
+    /// Custom tags shared by every render.
+    #[cfg(feature = "bindings")]
+    stylesheet: Option<Arc<Stylesheet>>,
 }
 
 impl<O> Renderer<O> {
@@ -38,76 +166,316 @@ impl<O> Renderer<O> {
             skip_ws: true,
             pre_ws: 1,
             block_start: true,
+            format: Format::Ansi,
+            escapes_only: false,
+            color_depth: ColorDepth::TrueColor,
+            policy: Policy::Always,
+            terminal: false,
+            styled: true,
+            width: None,
+            blocks: Vec::new(),
+            gradients: Vec::new(),
+            lists: Vec::new(),
+            tables: Vec::new(),
+            wrap_at: None,
+            html_styles: HashMap::new(),
+            #[cfg(feature = "bindings")]
+            stylesheet: None,
         }
     }
 
-    fn write_input<'src, I: ?Sized + Input>(&mut self, input: &'src I) -> Result<'src, I, ()> {
-        self.buf.clear();
+    /// Sets the format of the output.
+    #[must_use]
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets whether only escape sequences are interpreted.
+    ///
+    /// When set, tags are not parsed and `<` and `>` are written as text, like `echo -e`.
+    #[must_use]
+    pub fn with_escapes_only(mut self, escapes_only: bool) -> Self {
+        self.escapes_only = escapes_only;
+        self
+    }
+
+    /// Sets the color depth of the output.
+    ///
+    /// Colors that can not be shown with `depth` are replaced with the nearest supported
+    /// color. Use [`ColorDepth::detect`] to find the depth of the terminal. HTML output
+    /// always keeps the exact colors.
+    #[must_use]
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        self.color_depth = depth;
+        self
+    }
+
+    /// Sets when styles are written.
+    ///
+    /// [`Policy::Auto`] only writes styles when the output is a terminal, see
+    /// [`Renderer::with_terminal`]. HTML output is not written to a terminal and always has
+    /// styles.
+    #[must_use]
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets whether the output is a terminal. The output is not a terminal by default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use ziyy_core as ziyy;
+    /// use std::io::{self, IsTerminal};
+    /// use ziyy::renderer::{Policy, Renderer};
+    ///
+    /// let mut r = Renderer::new(io::stderr())
+    ///     .with_policy(Policy::Auto)
+    ///     .with_terminal(io::stderr().is_terminal());
+    /// r.write_str("<b>warning:</b> disk almost full\n").unwrap();
+    /// ```
+    #[must_use]
+    pub fn with_terminal(mut self, terminal: bool) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Sets the width lines are wrapped at. `None` does not wrap lines.
+    ///
+    /// Lines are broken at whitespace, text in `<pre>` elements is never wrapped. The
+    /// `width` attribute of `<div>`, `<p>`, `<pre>` and `<ziyy>` overrides this width for
+    /// the content of the element.
+    #[must_use]
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the width lines are wrapped at to the width of the terminal.
+    ///
+    /// See [`terminal_width`].
+    #[must_use]
+    pub fn with_terminal_width(self) -> Self {
+        self.with_width(terminal_width())
+    }
+
+    /// Sets the stylesheet whose custom tags every render can use.
+    #[must_use]
+    #[cfg(feature = "bindings")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bindings")))]
+    pub fn with_stylesheet(mut self, stylesheet: Stylesheet) -> Self {
+        self.stylesheet = Some(Arc::new(stylesheet));
+        self
+    }
+
+    /// Renders `input` to the buffer.
+    ///
+    /// When `errors` is given, errors are collected in it instead of being returned and the
+    /// rest of a malformed tag is skipped.
+    fn write_input<'src, I: ?Sized + Input>(
+        &mut self,
+        input: &'src I,
+        args: Option<template::Args>,
+        errors: Option<&mut Vec<Error<'src, I>>>,
+    ) -> Result<'src, I, ()> {
         let mut ctx = Context::new(input, None);
+        #[cfg(feature = "bindings")]
+        ctx.stylesheet.clone_from(&self.stylesheet);
+        self.write_context(&mut ctx, args, errors)
+    }
+
+    /// Renders the input of `ctx` to the buffer, like [`Renderer::write_input`].
+    pub(crate) fn write_context<'src, I: ?Sized + Input>(
+        &mut self,
+        ctx: &mut Context<'src, I>,
+        mut args: Option<template::Args>,
+        mut errors: Option<&mut Vec<Error<'src, I>>>,
+    ) -> Result<'src, I, ()> {
+        self.buf.clear();
+        self.blocks.clear();
+        self.gradients.clear();
+        self.lists.clear();
+        self.tables.clear();
+        self.wrap_at = None;
+        self.html_styles.clear();
+        self.styled = self.should_style();
+        ctx.scanner.escapes_only = self.escapes_only;
 
         loop {
-            let parsed = Parser::parse(&mut ctx)?;
-            match parsed {
-                Chunk::Comment(_, _) => {}
-                Chunk::Escape(ch, _) => {
-                    match ch.len_utf8() {
-                        1 => self.buf.push(ch as u8),
-                        n => {
-                            let mut buf = [0; 4];
-                            ch.encode_utf8(&mut buf);
-                            self.buf.extend_from_slice(&buf[0..n]);
+            match self.write_chunk(ctx, args.as_mut()) {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(error) => match errors.as_deref_mut() {
+                    Some(errors) => {
+                        errors.push(error);
+                        let self_closing = ctx.scanner.skip_tag();
+                        // Renders the tag without its attributes, so its close tag
+                        // still matches.
+                        if let Some(mut tag) = ctx.malformed_tag.take()
+                            && let Err(error) = match tag.kind {
+                                TagKind::Open if self_closing => {
+                                    tag.kind = TagKind::SelfClose;
+                                    self.render_self_close_tag(ctx, &tag)
+                                }
+                                TagKind::Open => self.render_open_tag(ctx, tag, args.as_mut()),
+                                _ => self.render_close_tag(ctx, &tag),
+                            }
+                        {
+                            errors.push(error);
                         }
                     }
+                    None => return Err(error),
+                },
+            }
+        }
+
+        loop {
+            let from = ctx.state.current();
+            let Some(diff) = ctx.state.pop() else {
+                break;
+            };
+            self.write_style(diff, from, ctx.state.current());
+        }
+
+        Ok(())
+    }
 
-                    self.skip_ws = is_whitespace(ch);
-                    self.block_start = self.skip_ws;
+    /// Renders the next chunk of input. Returns `true` at the end of input.
+    ///
+    /// When `args` is given, text is a template and its placeholders are replaced.
+    fn write_chunk<'src, I: ?Sized + Input>(
+        &mut self,
+        ctx: &mut Context<'src, I>,
+        args: Option<&mut template::Args>,
+    ) -> Result<'src, I, bool> {
+        let parsed = Parser::parse(ctx)?;
+        match parsed {
+            Chunk::Comment(_, _) => {}
+            Chunk::Escape(ch, _) => {
+                let mut buf = [0; 4];
+                let ch_bytes = ch.encode_utf8(&mut buf).as_bytes();
+                self.write_text(ch_bytes);
+                if !is_whitespace(ch) {
+                    self.wrap();
                 }
 
-                Chunk::Tag(tag) => match tag.kind {
-                    TagKind::Open => self.render_open_tag(&mut ctx, tag)?,
-                    TagKind::Close => self.render_close_tag(&mut ctx, &tag)?,
-                    TagKind::SelfClose => self.render_self_close_tag(&mut ctx, &tag)?,
-                },
+                self.skip_ws = is_whitespace(ch);
+                self.block_start = self.skip_ws;
+            }
 
-                Chunk::Text(text, _) => {
-                    self.buf.extend_from_slice(text.as_ref());
-                    self.skip_ws = false;
-                    self.block_start = false;
+            Chunk::Tag(tag) => match tag.kind {
+                TagKind::Open => self.render_open_tag(ctx, tag, args)?,
+                TagKind::Close => self.render_close_tag(ctx, &tag)?,
+                TagKind::SelfClose => self.render_self_close_tag(ctx, &tag)?,
+            },
+
+            Chunk::Text(text, span) => {
+                if self.outside_cell() {
+                    return Err(Error {
+                        kind: ErrorKind::TextOutsideCell(text),
+                        span,
+                    });
+                }
+                match args {
+                    Some(args) => self.write_template(ctx, text, span, args)?,
+                    None => self.write_text(text.as_ref()),
                 }
+                self.wrap();
+                self.skip_ws = false;
+                self.block_start = false;
+            }
 
-                Chunk::WhiteSpace(ws, _) => {
-                    let chunk = Parser::parse_next(&mut ctx)?;
-                    if self.pre_ws > 0 {
+            Chunk::WhiteSpace(ws, _) => {
+                // The whitespace is written even if the next chunk is malformed.
+                let chunk = Parser::parse_next(ctx);
+                if self.pre_ws > 0 {
+                    if ws.as_ref().contains(&b'\n') {
                         self.buf.extend_from_slice(ws.as_ref());
-                    } else if let Chunk::Eof(_) = chunk {
-                        if ws.as_ref().contains(&b'\n') {
-                            self.buf.push(b'\n');
-                        }
-                    } else if !self.skip_ws {
-                        self.buf.push(b' ');
-                        self.skip_ws = true;
+                    } else {
+                        self.write_break(ws.as_ref(), ctx.state.current());
                     }
-                }
-
-                Chunk::Eof(_) => {
-                    while let Some(diff) = ctx.state.pop() {
-                        self.buf
-                            .extend_from_slice(&diff.not().to_string2().as_bytes());
+                } else if let Ok(Chunk::Eof(_)) = chunk {
+                    if ws.as_ref().contains(&b'\n') {
+                        self.buf.push(b'\n');
                     }
-                    return Ok(());
+                } else if !self.skip_ws {
+                    self.write_break(b" ", ctx.state.current());
+                    self.skip_ws = true;
                 }
+                chunk?;
             }
+
+            Chunk::Eof(_) => return Ok(true),
         }
+
+        Ok(false)
     }
 
     fn write_and_save<'src, I: ?Sized + Input>(
         &mut self,
         ctx: &mut Context<'src, I>,
-        name: &TagName<'src, I>,
-        style: Style,
+        tag: &Tag<'src, I>,
     ) {
-        let diff = ctx.state.push(name.clone(), style);
-        self.buf.extend_from_slice(&diff.to_string2().as_ref());
+        let from = ctx.state.current();
+        let diff = ctx.state.push(tag.name.clone(), tag.style, tag.span);
+        self.write_style(diff, from, ctx.state.current());
+    }
+
+    /// Returns whether styles are written to the output.
+    fn should_style(&self) -> bool {
+        match self.format {
+            Format::Ansi => self.policy.resolve(self.terminal),
+            Format::Html => true,
+            Format::Plain => false,
+        }
+    }
+
+    /// Writes text, escaped for HTML in the HTML format.
+    fn write_text(&mut self, text: &[u8]) {
+        if self.format == Format::Html {
+            html::escape(text, &mut self.buf);
+        } else {
+            self.buf.extend_from_slice(text);
+        }
+    }
+
+    /// Writes a change from the style `from` to the style `to`, as the escape sequence of
+    /// `diff` or as HTML elements.
+    fn write_style(&mut self, diff: Style, from: Style, to: Style) {
+        if self.format == Format::Html {
+            if self.styled {
+                self.write_span(from, to);
+            }
+        } else {
+            let sgr = self.sgr(diff);
+            self.buf.extend_from_slice(sgr.as_bytes());
+        }
+    }
+
+    /// Writes the change that goes back from the style `from` to the style `to`.
+    fn write_revert(&mut self, to: Style, from: Style) {
+        self.write_style(revert(to, from), from, to);
+    }
+
+    /// Returns the escape sequence of a style change, empty when styles are not written.
+    fn sgr(&self, mut diff: Style) -> String {
+        if self.color_depth != ColorDepth::TrueColor {
+            diff.set_fg_color(diff.fg_color().downsample(self.color_depth));
+            diff.set_bg_color(diff.bg_color().downsample(self.color_depth));
+            diff.set_ul_color(match self.color_depth {
+                ColorDepth::Ansi256 => diff.ul_color().downsample(self.color_depth),
+                // there is no escape sequence for 16 underline colors
+                _ => Color::None,
+            });
+        }
+
+        if self.styled {
+            diff.to_string2()
+        } else {
+            String::new()
+        }
     }
 }
 
@@ -115,6 +483,7 @@ impl<O> Renderer<O> {
 impl<'src, I: ?Sized + Input> Renderer<Tree<'src, I>> {
     pub fn render(self, input: &'src I) -> Result<'src, I, Tree<'src, I>> {
         let mut ctx = Context::new(input, None);
+        ctx.scanner.escapes_only = self.escapes_only;
         let mut doc = self.output;
         let mut id = doc.root().id();
 
@@ -170,27 +539,116 @@ impl<O: fmt::Write> Renderer<O> {
 
 impl Renderer<String> {
     pub fn render<'src>(mut self, input: &'src str) -> Result<'src, str, String> {
-        self.write_input(input)?;
+        self.write_input(input, None, None)?;
         self.output.reserve_exact(self.buf.len());
 
         self.write_buf_to_output()?;
 
         Ok(self.output)
     }
+
+    /// Renders the template `input`, replacing each `{}` outside of tags with the next of
+    /// `args`. `{{` and `}}` are written as `{` and `}`, and extra arguments are ignored.
+    ///
+    /// Arguments are written as text: tags in them are not parsed and their control
+    /// characters are dropped, so untrusted text can not change styles or write escape
+    /// sequences.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `input` is malformed or has more placeholders than `args`.
+    pub fn render_args<'src>(
+        mut self,
+        input: &'src str,
+        args: &[&dyn fmt::Display],
+    ) -> Result<'src, str, String> {
+        self.write_input(input, Some(template::Args::new(args)), None)?;
+        self.output.reserve_exact(self.buf.len());
+
+        self.write_buf_to_output()?;
+
+        Ok(self.output)
+    }
+
+    /// Renders `input`, recovering from errors.
+    ///
+    /// Malformed tags are skipped and rendering goes on. Returns the best-effort output with
+    /// all errors found.
+    #[must_use]
+    pub fn render_recovering(mut self, input: &str) -> (String, Vec<Error<'_, str>>) {
+        let mut errors = Vec::new();
+        let _ = self.write_input(input, None, Some(&mut errors));
+        self.output.reserve_exact(self.buf.len());
+
+        if let Err(error) = self.write_buf_to_output() {
+            errors.push(error.into());
+        }
+
+        (self.output, errors)
+    }
 }
 
 impl<O: io::Write> Renderer<O> {
     pub fn write_str<'src>(&mut self, s: &'src str) -> Result<'src, str, ()> {
-        self.write_input(s)?;
+        self.write_input(s, None, None)?;
         self.output.write_all(&self.buf)?;
 
         Ok(())
     }
+
+    /// Writes `s` to the output, recovering from errors.
+    ///
+    /// Malformed tags are skipped and rendering goes on. Returns all errors found.
+    pub fn write_str_recovering<'src>(&mut self, s: &'src str) -> Vec<Error<'src, str>> {
+        let mut errors = Vec::new();
+        let _ = self.write_input(s, None, Some(&mut errors));
+
+        if let Err(error) = self.output.write_all(&self.buf) {
+            errors.push(error.into());
+        }
+
+        errors
+    }
+
+    /// Replaces the current line of the output with `s`.
+    ///
+    /// The cursor is moved to the start of the line and the line is erased before `s` is
+    /// written, then the output is flushed. Calling it again redraws the line, which keeps
+    /// a `<progress/>` bar or a status line up to date. `s` should fit in one line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `s` is malformed or the output can not be written.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ziyy_core as ziyy;
+    /// use ziyy::Renderer;
+    ///
+    /// let mut r = Renderer::new(std::io::stdout());
+    /// for i in 0..=10 {
+    ///     let bar = format!("<progress value='{}' width='10'/> {i}0%", f64::from(i) / 10.0);
+    ///     r.rewrite_line(&bar).unwrap();
+    /// }
+    /// ```
+    pub fn rewrite_line<'src>(&mut self, s: &'src str) -> Result<'src, str, ()> {
+        self.write_input(s, None, None)?;
+        self.output.write_all(b"\r")?;
+        if self.styled {
+            // erases the whole line
+            self.output.write_all(b"\x1b[2K")?;
+        }
+        self.output.write_all(&self.buf)?;
+        self.output.flush()?;
+
+        Ok(())
+    }
 }
 
 impl<O: io::Write> io::Write for Renderer<O> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        match self.write_input(buf) {
+        match self.write_input(buf, None, None) {
             Ok(_) => {}
             Err(e) => return Err(io::Error::new(io::ErrorKind::Other, format!("{e:?}"))),
         }
@@ -206,7 +664,7 @@ impl<O: io::Write> io::Write for Renderer<O> {
 
 impl<O: fmt::Write> fmt::Write for Renderer<O> {
     fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        match self.write_input(s) {
+        match self.write_input(s, None, None) {
             Ok(_) => {}
             Err(_) => return Err(fmt::Error),
         }
@@ -222,9 +680,11 @@ impl<O: fmt::Write> fmt::Write for Renderer<O> {
 
 #[cfg(test)]
 mod tests {
-    use super::Renderer;
+    use super::{ColorDepth, Format, Policy, Renderer};
+    use crate::error::ErrorKind;
     use std::cell::RefCell;
     use std::fmt;
+    use std::fs;
     use std::io;
     use std::io::Write as _;
     use std::rc::Rc;
@@ -280,6 +740,585 @@ mod tests {
         assert_eq!(shared.borrow().as_str(), "formatted");
     }
 
+    #[test]
+    fn render_plain_drops_styles() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render("<ziyy><p><b c='red'>hello</b>\\n  <a href='x'>world</a></p></ziyy>")
+            .unwrap();
+        assert_eq!(out, "hello\nworld");
+    }
+
+    #[test]
+    fn render_escapes_only_keeps_tags_as_text() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_escapes_only(true);
+        let out = r.render("<b>a\\tb</b>\\x1b[1mc").unwrap();
+        assert_eq!(out, "<b>a\tb</b>\x1b[1mc\x1b[22m");
+    }
+
+    #[test]
+    fn render_sgr_sequences_in_text_change_the_style() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r.render("<b>a\x1b[31mb\x1b[0mc</b>d \\e[4me").unwrap();
+        assert_eq!(out, "\x1b[1ma\x1b[31mb\x1b[22;39mcd \x1b[4me\x1b[24m");
+    }
+
+    #[test]
+    fn render_html_uses_spans_and_anchors() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Html);
+        let out = r
+            .render("<span b c='#f00'>a</span><span u x='fixed(21)'>&b</span> <a href='x'>c</a>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "<span style=\"font-weight:bold;color:#ff0000\">a</span>\
+             <span style=\"background-color:#0000ff;text-decoration-line:underline\">&amp;b</span> \
+             <a href=\"x\">c</a>"
+        );
+    }
+
+    #[test]
+    fn render_html_lays_out_elements() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Html);
+            r.render(source).unwrap()
+        };
+        assert_eq!(
+            render("<p width='6'><u>aa bb cc</u></p>"),
+            "<span style=\"text-decoration-line:underline\">aa bb</span>\n\
+             <span style=\"text-decoration-line:underline\">cc</span>"
+        );
+        assert_eq!(
+            render("<box>\x1b[1ma&</box>"),
+            "╭────╮\n│ <span style=\"font-weight:bold\">a&amp;</span> │\n╰────╯"
+        );
+        assert_eq!(
+            render("<gradient from='#000' to='#fff'>ab<b>c</b></gradient>"),
+            "<span style=\"color:#000000\">a</span><span style=\"color:#808080\">b</span>\
+             <span style=\"font-weight:bold;color:#ffffff\">c</span>"
+        );
+    }
+
+    #[test]
+    fn render_policy_never_drops_styles() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_policy(Policy::Never);
+        let out = r.render("<b>a</b> <a href='x'>b</a>").unwrap();
+        assert_eq!(out, "a b");
+    }
+
+    #[test]
+    fn render_policy_auto_checks_the_output() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_policy(Policy::Auto);
+        assert!(!r.should_style());
+        let out = r.render("<b>a</b> <a href='x'>b</a>").unwrap();
+        assert_eq!(out, "a b");
+
+        let mut buf = Vec::new();
+        let mut r = Renderer::new(&mut buf).with_policy(Policy::Auto);
+        r.write_str("<b>a</b>").unwrap();
+        assert_eq!(buf, b"a");
+    }
+
+    #[test]
+    fn render_html_ignores_policy_and_color_depth() {
+        let r: Renderer<String> = Renderer::new(String::new())
+            .with_format(Format::Html)
+            .with_policy(Policy::Auto)
+            .with_color_depth(ColorDepth::Ansi16);
+        let null = fs::File::open("/dev/null").unwrap();
+        assert!(!Policy::Auto.should_style(&null));
+        assert!(r.should_style());
+        let out = r.render("<c rgb='255,135,0'>a</c>").unwrap();
+        assert_eq!(out, "<span style=\"color:#ff8700\">a</span>");
+    }
+
+    #[test]
+    fn render_downsamples_colors() {
+        let render = |depth| {
+            let r: Renderer<String> = Renderer::new(String::new()).with_color_depth(depth);
+            r.render("<c rgb='255,135,0'>a</c>").unwrap()
+        };
+        assert_eq!(render(ColorDepth::Ansi256), "\x1b[38;5;208ma\x1b[39m");
+        assert_eq!(render(ColorDepth::Ansi16), "\x1b[33ma\x1b[39m");
+        assert_eq!(render(ColorDepth::None), "a");
+    }
+
+    #[test]
+    fn render_underline_styles() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new());
+            r.render(source).unwrap()
+        };
+        assert_eq!(render("<u curly>a</u>"), "\x1b[4:3ma\x1b[24m");
+        assert_eq!(render("<u dotted>a</u>"), "\x1b[4:4ma\x1b[24m");
+        assert_eq!(render("<u dashed>a</u>"), "\x1b[4:5ma\x1b[24m");
+        assert_eq!(render("<span u='curly'>a</span>"), "\x1b[4:3ma\x1b[24m");
+        assert_eq!(render("<b u='dashed'>a</b>"), "\x1b[1;4:5ma\x1b[22;24m");
+        assert_eq!(render("<uu>a</uu>"), "\x1b[21ma\x1b[24m");
+        assert_eq!(
+            render("<u>a<u dotted>b</u>c</u>"),
+            "\x1b[4ma\x1b[4:4mb\x1b[4mc\x1b[24m"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "uncommon")]
+    fn render_uncommon_styles() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new());
+            r.render(source).unwrap()
+        };
+        assert_eq!(render("<span o>a</span>"), "\x1b[53ma\x1b[55m");
+        assert_eq!(render("<span framed>a</span>"), "\x1b[51ma\x1b[54m");
+        assert_eq!(render("<span encircled>a</span>"), "\x1b[52ma\x1b[54m");
+        assert_eq!(render("<span font='3'>a</span>"), "\x1b[13ma\x1b[10m");
+        assert_eq!(render("<span proportional>a</span>"), "\x1b[26ma\x1b[50m");
+    }
+
+    #[test]
+    fn render_fraktur_and_fast_blink() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new());
+            r.render(source).unwrap()
+        };
+        assert_eq!(render("<k fast>a</k>"), "\x1b[6ma\x1b[25m");
+        assert_eq!(render("<span fraktur>a</span>"), "\x1b[20ma\x1b[23m");
+        assert_eq!(
+            render("<i>a<i fraktur>b</i>c</i>"),
+            "\x1b[3ma\x1b[23;20mb\x1b[23;3mc\x1b[23m"
+        );
+        assert_eq!(
+            render("<k>a<k fast>b</k>c</k>"),
+            "\x1b[5ma\x1b[25;6mb\x1b[25;5mc\x1b[25m"
+        );
+    }
+
+    #[test]
+    fn render_underline_colors() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<let id='err' u ul='red' /><err>a</err><u ul='fixed(9)'>b</u>")
+            .unwrap();
+        assert_eq!(out, "\x1b[4;58;5;1ma\x1b[24;59m\x1b[4;58;5;9mb\x1b[24;59m");
+    }
+
+    #[test]
+    fn render_nested_tags_restore_parent_style() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<b>a<d>b</d>c<c red>d<c blue>e</c>f</c></b>g")
+            .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[1ma\x1b[22;2mb\x1b[22;1mc\x1b[31md\x1b[34me\x1b[31mf\x1b[39m\x1b[22mg"
+        );
+    }
+
+    #[test]
+    fn render_nested_bold_and_dim_close_in_order() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r.render("<d>a<b>b</b>c</d>d<b><d>e</d></b>f").unwrap();
+        assert_eq!(
+            out,
+            "\x1b[2ma\x1b[22;1mb\x1b[22;2mc\x1b[22md\x1b[1m\x1b[22;2me\x1b[22;1m\x1b[22mf"
+        );
+    }
+
+    #[test]
+    fn render_bold_inside_dim_inside_bold() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r.render("<b>a<d>b<b>c</b>d</d>e</b>f").unwrap();
+        assert_eq!(
+            out,
+            "\x1b[1ma\x1b[22;2mb\x1b[22;1mc\x1b[22;2md\x1b[22;1me\x1b[22mf"
+        );
+    }
+
+    #[test]
+    fn render_recovering_collects_all_errors() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let (out, errors) = r.render_recovering("<b c='gren'>a</b> <i>b</b>\n<c rgb='1,x'>c</c>");
+        assert_eq!(out, "a b\nc");
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn render_recovering_opens_elements_with_invalid_attributes() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+            let (out, errors) = r.render_recovering(source);
+            (out, errors.into_iter().map(|e| e.kind).collect::<Vec<_>>())
+        };
+        let (out, errors) = render("<ol start='-1'><li>a</li><li>b</li></ol>");
+        assert_eq!(out, "1. a\n2. b");
+        assert!(matches!(errors[..], [ErrorKind::InvalidNumber { .. }]));
+        let (out, errors) = render("<div align='middle' pad='1'>a</div>b");
+        assert_eq!(out, " a b");
+        assert!(matches!(errors[..], [ErrorKind::InvalidAlign("middle")]));
+        let (out, errors) = render("<table border='thin'><tr><td>a</td></tr></table>b");
+        assert_eq!(out, "ab");
+        assert!(matches!(errors[..], [ErrorKind::InvalidBorder("thin")]));
+    }
+
+    #[test]
+    fn render_wraps_lines() {
+        let render = |width, source| {
+            let r: Renderer<String> = Renderer::new(String::new()).with_width(width);
+            r.render(source).unwrap()
+        };
+        assert_eq!(
+            render(Some(10), "<ziyy>aaa bbb ccc\n  dddd eeeeeeeeeeee f</ziyy>"),
+            "aaa bbb\nccc dddd\neeeeeeeeeeee\nf"
+        );
+        assert_eq!(render(Some(5), "你好 世界"), "你好\n世界");
+        assert_eq!(
+            render(Some(2), "<x red>a <u>b</u></x>"),
+            "\x1b[41ma\x1b[49m\n\x1b[41m\x1b[4mb\x1b[24m\x1b[49m"
+        );
+        assert_eq!(
+            render(Some(5), "<u>aa\\e[1mb c</u>"),
+            "\x1b[4maa\x1b[1mb c\x1b[22;24m"
+        );
+        assert_eq!(render(None, "<p width='2'>a b</p> c d"), "a\nb c d");
+        assert_eq!(render(Some(3), "<pre>a b c</pre>"), "a b c");
+    }
+
+    #[test]
+    fn render_aligns_and_pads_blocks() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+            r.render(source).unwrap()
+        };
+        assert_eq!(render("<p align='center' width='7'>abc</p>"), "  abc");
+        assert_eq!(
+            render("<ziyy><p align='right' width='8'>aaa bbb c</p></ziyy>"),
+            " aaa bbb\n       c"
+        );
+        assert_eq!(render("<div margin='2'>a\nb</div>"), "  a\n  b");
+        // without a width, lines are padded to the widest one
+        assert_eq!(
+            render("<div pad='1' align='right'>a\nbbb</div>"),
+            "   a \n bbb "
+        );
+        assert_eq!(
+            render("<p pad='1' margin='1' width='7'>a b c</p>"),
+            "  a b \n  c   "
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        assert_eq!(
+            r.render("<p pad='1' x='red'>a</p>").unwrap(),
+            "\x1b[41m \x1b[49m\x1b[41ma\x1b[49m\x1b[41m \x1b[49m"
+        );
+    }
+
+    #[test]
+    fn render_rejects_layout_numbers_over_the_limit() {
+        let render = |source| {
+            let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+            r.render(source)
+        };
+        assert_eq!(render("<div margin='1000'></div>").unwrap(), "");
+        for source in [
+            "<div margin='9999999'>a</div>",
+            "<div width='1001'>a</div>",
+            "<p pad='70000'>a</p>",
+            "<p indent='1001'>a</p>",
+            "<br n='1001'/>",
+            "<progress width='1001'/>",
+        ] {
+            let error = render(source).unwrap_err();
+            assert!(
+                matches!(error.kind(), ErrorKind::InvalidNumber { max: 1000, .. }),
+                "{source}: {error:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn render_tables() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render(
+                "<ziyy><table>\
+                 <tr><td>a</td><td>bbb</td><td>c</td></tr>\
+                 <tr><td>dddd</td><td align='right'>e</td></tr>\
+                 </table></ziyy>",
+            )
+            .unwrap();
+        assert_eq!(out, "a     bbb  c\ndddd    e");
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render(
+                "<table border='rounded'>\
+                 <tr><th>Name</th><th>Size</th></tr>\
+                 <tr><td>a<br/>b</td><td>你好</td></tr>\
+                 </table>",
+            )
+            .unwrap();
+        assert_eq!(
+            out,
+            "╭──────┬──────╮\n\
+             │ Name │ Size │\n\
+             ├──────┼──────┤\n\
+             │ a    │ 你好 │\n\
+             │ b    │      │\n\
+             ╰──────┴──────╯"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<table border c='blue'><tr><td x='red'>a</td><td>bb</td></tr></table>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[34m┌───┬────┐\n\
+             │ \x1b[41ma\x1b[49m │ bb │\n\
+             └───┴────┘\x1b[39m"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render("<table border><tr></tr><tr><td>a</td></tr></table><table border><tr/></table>")
+            .unwrap();
+        assert_eq!(out, "┌───┐\n│ a │\n└───┘\n");
+
+        for source in [
+            "<table>a<tr><td>b</td></tr></table>",
+            "<table><tr>a<td>b</td></tr></table>",
+        ] {
+            let r: Renderer<String> = Renderer::new(String::new());
+            let error = r.render(source).unwrap_err();
+            assert!(
+                matches!(error.kind(), ErrorKind::TextOutsideCell(_)),
+                "{error:?}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bindings")]
+    fn render_let_rejects_builtin_tag_names() {
+        let reserved = |name| {
+            let r: Renderer<String> = Renderer::new(String::new());
+            let source = format!("<let id='{name}' c='red'></let>");
+            matches!(
+                r.render(source.as_str()).map_err(|error| error.kind),
+                Err(ErrorKind::BuiltinTagOverwrite(_))
+            )
+        };
+        for name in [
+            "b", "table", "tr", "td", "th", "ul", "ol", "li", "box", "hr", "gradient", "progress",
+        ] {
+            assert!(reserved(name), "{name}");
+        }
+        // headings are restyled with bindings of the same name
+        assert!(!reserved("h1"));
+    }
+
+    #[test]
+    fn render_lists() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render(
+                "<ziyy><ul>
+                   <li>a</li>
+                   <li>b<ol start='9'><li>nine</li><li>ten</li></ol></li>
+                 </ul>
+                 <ul bullet='-'><li>c</li></ul></ziyy>",
+            )
+            .unwrap();
+        assert_eq!(out, "• a\n• b\n  9. nine\n  10. ten\n- c");
+
+        let r: Renderer<String> = Renderer::new(String::new())
+            .with_format(Format::Plain)
+            .with_width(Some(12));
+        let out = r.render("<ol><li>one two three four</li></ol>").unwrap();
+        assert_eq!(out, "1. one two\n   three\n   four");
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Html);
+        let out = r.render("<ul bullet='<b>'><li>a &</li></ul>").unwrap();
+        assert_eq!(out, "&lt;b&gt; a &amp;");
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render("<ziyy><ol start='4294967295'><li>a</li><li>b</li></ol></ziyy>")
+            .unwrap();
+        assert_eq!(out, "4294967295. a\n4294967296. b");
+    }
+
+    #[test]
+    fn render_boxes() {
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render("<box title='Error'>not found<br/>try again</box>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "╭─ Error ───╮\n\
+             │ not found │\n\
+             │ try again │\n\
+             ╰───────────╯"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render("<box border='double' width='12' align='right'>one two three</box>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "╔══════════╗\n\
+             ║  one two ║\n\
+             ║    three ║\n\
+             ╚══════════╝"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<box border='single' border-color='red' pad='0'><b>a</b>b</box>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[31m┌──┐\x1b[39m\n\
+             \x1b[31m│\x1b[39m\x1b[1ma\x1b[22mb\x1b[31m│\x1b[39m\n\
+             \x1b[31m└──┘\x1b[39m"
+        );
+    }
+
+    #[test]
+    fn render_rules() {
+        let r: Renderer<String> = Renderer::new(String::new())
+            .with_format(Format::Plain)
+            .with_width(Some(10));
+        let out = r.render("a<hr/>b<hr char='=' title='Sec'/>").unwrap();
+        assert_eq!(out, "a\n──────────\nb\n== Sec ===\n");
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_width(Some(4));
+        let out = r.render("<hr c='red'/>").unwrap();
+        assert_eq!(out, "\x1b[31m────\x1b[39m\n");
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_format(Format::Plain);
+        let out = r
+            .render("<hr char='-'/><div width='6'><hr/></div>")
+            .unwrap();
+        assert_eq!(out, format!("{}\n──────\n", "-".repeat(80)));
+    }
+
+    #[test]
+    fn render_headings() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r.render("<h1>a</h1><h2>b</h2><h3>c</h3>").unwrap();
+        assert_eq!(
+            out,
+            "\x1b[1;4ma\x1b[22;24m\n\x1b[1mb\x1b[22m\n\x1b[3mc\x1b[23m"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<let id='h1' c='blue'/><h1>a</h1><h2 class='u'>b</h2>")
+            .unwrap();
+        assert_eq!(out, "\x1b[34ma\x1b[39m\n\x1b[1;4mb\x1b[22;24m");
+    }
+
+    #[test]
+    fn render_gradients() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<gradient from='#ff0000' to='#0000ff'>abc</gradient>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[38;2;255;0;0ma\x1b[38;2;128;0;128mb\x1b[38;2;0;0;255mc\x1b[39m"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<gradient stops='#000, rgb(0, 0, 255), #000' bg>a<b>bc</b></gradient>")
+            .unwrap();
+        assert_eq!(
+            out,
+            "\x1b[48;2;0;0;0ma\x1b[1m\x1b[48;2;0;0;255mb\x1b[48;2;0;0;0mc\x1b[22m\x1b[49m"
+        );
+
+        let r: Renderer<String> = Renderer::new(String::new()).with_color_depth(ColorDepth::Ansi16);
+        let out = r
+            .render("<gradient from='#ff0000' to='#0000ff'>ab</gradient>")
+            .unwrap();
+        assert_eq!(out, "\x1b[91ma\x1b[34mb\x1b[39m");
+    }
+
+    #[test]
+    fn render_progress() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render("<progress value='0.5' width='4' fill-color='green'/> 50%")
+            .unwrap();
+        assert_eq!(out, "\x1b[32m██\x1b[39m░░ 50%");
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render(
+                "<let id='progress-empty' d/>\
+                 <progress value='25%' width='4' fill='=' empty='-'/>",
+            )
+            .unwrap();
+        assert_eq!(out, "=\x1b[2m---\x1b[22m");
+    }
+
+    #[test]
+    fn rewrite_line() {
+        let mut r = Renderer::new(Vec::new());
+        r.rewrite_line("<progress value='0' width='2'/>").unwrap();
+        r.rewrite_line("<progress value='1' width='2'/>").unwrap();
+        assert_eq!(r.output, "\r\x1b[2K░░\r\x1b[2K██".as_bytes());
+    }
+
+    #[test]
+    fn render_args() {
+        let r: Renderer<String> = Renderer::new(String::new());
+        let name = "<b>x</b>\x1b]8;;a\x07";
+        let out = r
+            .render_args("<i>{} {{{}}} {}</i>", &[&name, &1, &'}'])
+            .unwrap();
+        assert_eq!(out, "\x1b[3m<b>x</b>]8;;a {1} }\x1b[23m");
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        let out = r
+            .render_args("<a href='x'>{} {{}}</a> {}", &[&"<b>a</b>", &2])
+            .unwrap();
+        assert_eq!(out, "\x1b]8;;x\x1b\\<b>a</b> {}\x1b]8;;\x1b\\ 2");
+
+        let r: Renderer<String> = Renderer::new(String::new());
+        assert!(r.render_args("{} {}", &[&1]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "bindings")]
+    fn render_stylesheet() {
+        use crate::Stylesheet;
+        use crate::style::{AnsiColor, Color, Style};
+
+        let mut sheet = Stylesheet::parse("<let id='error' c='red'/>\n<let id='path' u/>").unwrap();
+        let mut style = Style::new();
+        style.set_fg_color(Color::AnsiColor(AnsiColor::Green));
+        sheet.define("ok", style).unwrap();
+        // like <let>, the stylesheet can not replace builtin tags
+        let error = sheet.define("b", style).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::BuiltinTagOverwrite("b")));
+        assert!(sheet.define("h1", style).is_ok());
+
+        let mut r = Renderer::new(Vec::new()).with_stylesheet(sheet);
+        r.write_str("<error>e</error> <ok>o</ok>").unwrap();
+        // bindings of the input take precedence
+        r.write_str("<let id='path' b/><path>p</path> <span class='error'>e</span>")
+            .unwrap();
+        assert_eq!(
+            r.output,
+            "\x1b[31me\x1b[39m \x1b[32mo\x1b[39m\x1b[1mp\x1b[22m \x1b[31me\x1b[39m".as_bytes()
+        );
+    }
+
     #[test]
     fn render_preserves_leading_whitespace() {
         let r: Renderer<String> = Renderer::new(String::new());
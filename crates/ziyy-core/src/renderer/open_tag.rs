@@ -3,29 +3,30 @@ use std::collections::HashMap;
 use crate::builtins::BUILTIN_STYLES;
 use crate::context::Context;
 use crate::error::{Error, ErrorKind, Result};
-use crate::num::input_to_u8;
+use crate::num::input_to_size;
 use crate::parser::{Chunk, Parser, Tag, TagKind, TagName};
 use crate::shared::{Input, Value};
 
-use super::Renderer;
+use super::{Format, Renderer, attr_or, html, template};
 
 impl<O> Renderer<O> {
+    /// Renders an open tag. When `args` is given, the text of a link is a template.
     #[allow(clippy::too_many_lines)]
     pub(super) fn render_open_tag<'src, I: ?Sized + Input>(
         &mut self,
         ctx: &mut Context<'src, I>,
         mut tag: Tag<'src, I>,
+        mut args: Option<&mut template::Args>,
     ) -> Result<'src, I, ()> {
         match tag.name {
             TagName::A => {
-                self.buf.extend_from_slice(b"\x1b]8;;");
-                self.buf
-                    .extend_from_slice(if let Value::Some(href) = tag.custom {
-                        href.as_ref()
-                    } else {
-                        b""
-                    });
-                self.buf.extend_from_slice(b"\x1b\\");
+                if self.styled {
+                    let href = match tag.custom {
+                        Value::Some(href) => href.as_ref(),
+                        _ => b"",
+                    };
+                    self.write_link_start(href);
+                }
                 loop {
                     let chunk = Parser::parse(ctx)?;
                     match chunk {
@@ -36,9 +37,10 @@ impl<O> Renderer<O> {
                             }
                         }
 
-                        Chunk::Text(text, _) => {
-                            self.buf.extend_from_slice(text.as_ref());
-                        }
+                        Chunk::Text(text, span) => match args.as_deref_mut() {
+                            Some(args) => self.write_template(ctx, text, span, args)?,
+                            None => self.write_text(text.as_ref()),
+                        },
 
                         Chunk::WhiteSpace(ws, _) => {
                             self.buf.extend_from_slice(ws.as_ref());
@@ -51,17 +53,18 @@ impl<O> Renderer<O> {
                             });
                         }
                     }
-                    self.buf.extend_from_slice(b"\x1b]8;;\x1b\\");
+                }
+                if self.styled {
+                    self.write_link_end();
                 }
             }
             TagName::Any(s) => {
-                if let Some(bindings) = &ctx.bindings {
-                    let src = bindings.get(s.as_ref());
-                    if let Some(btag) = src {
+                if ctx.has_bindings() {
+                    if let Some(btag) = ctx.binding(s.as_ref()) {
                         tag.inherit(btag);
                     }
 
-                    self.write_and_save(ctx, &tag.name, tag.style);
+                    self.write_and_save(ctx, &tag);
                 }
             }
             TagName::Ansi
@@ -83,38 +86,23 @@ impl<O> Renderer<O> {
                     self.pre_ws -= 1;
                 }
 
-                if let Value::Some(s) = tag.class {
-                    for class in s
-                        .as_ref()
-                        .split(|n| *n == b' ')
-                        .filter(|s| !s.is_empty())
-                        .rev()
-                    {
-                        if let Some(btag) = BUILTIN_STYLES.get(class) {
-                            tag.inherit(btag);
-                        } else if let Some(bindings) = &ctx.bindings {
-                            if let Some(btag) = bindings.get(class) {
-                                tag.inherit(btag);
-                            }
-                        }
-                    }
+                inherit_classes(ctx, &mut tag);
+
+                if tag.name == TagName::Ziyy {
+                    let (start, parent) = (self.buf.len(), ctx.state.current());
+                    self.write_and_save(ctx, &tag);
+                    self.open_block(ctx, &tag, start, parent)?;
+                } else {
+                    self.write_and_save(ctx, &tag);
                 }
-                self.write_and_save(ctx, &tag.name, tag.style);
             }
 
-            TagName::Br => loop {
+            TagName::Br | TagName::Hr | TagName::Progress => loop {
                 let chunk = Parser::parse(ctx)?;
                 match chunk {
                     Chunk::Tag(tag2) => {
-                        if tag2.name == TagName::Br && tag2.kind == TagKind::Close {
-                            if let Value::Some(val) = tag.custom {
-                                let n: usize = get_num2!(input_to_u8(val, 10), tag) as usize;
-                                for _ in 0..n {
-                                    self.buf.push(b'\n');
-                                }
-                            } else {
-                                self.buf.push(b'\n');
-                            }
+                        if tag2.name == tag.name && tag2.kind == TagKind::Close {
+                            self.render_self_close_tag(ctx, &tag)?;
                             break;
                         }
                     }
@@ -161,55 +149,162 @@ impl<O> Renderer<O> {
                     _ => {}
                 }
             },
-            TagName::Div | TagName::P | TagName::Pre => {
+            TagName::Div | TagName::P | TagName::Pre | TagName::H1 | TagName::H2 | TagName::H3 => {
                 if !self.block_start {
                     self.buf.push(b'\n');
                     self.block_start = true;
                 }
 
+                let (start, parent) = (self.buf.len(), ctx.state.current());
                 if tag.name == TagName::Pre {
                     self.pre_ws += 1;
                 }
 
-                if let Value::Some(s) = tag.class {
-                    for class in s
-                        .as_ref()
-                        .split(|n| *n == b' ')
-                        .filter(|s| !s.is_empty())
-                        .rev()
-                    {
-                        if let Some(btag) = BUILTIN_STYLES.get(class) {
-                            tag.inherit(btag);
-                        } else if let Some(bindings) = &ctx.bindings {
-                            if let Some(btag) = bindings.get(class) {
-                                tag.inherit(btag);
-                            }
-                        }
-                    }
-                }
+                inherit_classes(ctx, &mut tag);
+                inherit_heading(ctx, &mut tag);
 
+                let mut error = None;
                 match tag.custom {
                     Value::Bool => {
                         self.buf.push(b'\t');
                     }
 
                     Value::Some(val) => {
-                        let n: usize = get_num2!(input_to_u8(val, 10), tag) as usize;
-                        for _ in 0..n {
-                            self.buf.push(b' ');
-                        }
+                        let n = input_to_size(val).map_err(|kind| Error {
+                            kind,
+                            span: tag.spans.custom,
+                        });
+                        let n = attr_or(n, 0, &mut error);
+                        self.buf.resize(self.buf.len() + n, b' ');
                     }
 
                     Value::None => {}
                 }
 
-                self.write_and_save(ctx, &tag.name, tag.style);
+                self.write_and_save(ctx, &tag);
+                self.skip_ws = true;
+                let opened = self.open_block(ctx, &tag, start, parent);
+                error.map_or(opened, Err)?;
+            }
+            TagName::Gradient => {
+                inherit_classes(ctx, &mut tag);
+
+                self.write_and_save(ctx, &tag);
+                let start = self.buf.len();
+                self.open_gradient(&tag, start, ctx.state.current())?;
+            }
+            TagName::Box => {
+                if !self.block_start {
+                    self.buf.push(b'\n');
+                    self.block_start = true;
+                }
+
+                inherit_classes(ctx, &mut tag);
+
+                let (start, parent) = (self.buf.len(), ctx.state.current());
+                self.write_and_save(ctx, &tag);
+                self.skip_ws = true;
+                self.open_block(ctx, &tag, start, parent)?;
+            }
+            TagName::Table | TagName::Tr | TagName::Td | TagName::Th => {
+                if tag.name == TagName::Table && !self.block_start {
+                    self.buf.push(b'\n');
+                    self.block_start = true;
+                }
+
+                inherit_classes(ctx, &mut tag);
 
+                let (start, parent) = (self.buf.len(), ctx.state.current());
+                self.write_and_save(ctx, &tag);
                 self.skip_ws = true;
+                match tag.name {
+                    TagName::Table => self.open_table(ctx, &tag, start, parent)?,
+                    TagName::Tr => self.open_row(),
+                    _ => self.open_cell(&tag, start, parent)?,
+                }
+            }
+            TagName::Ul | TagName::Ol | TagName::Li => {
+                if !self.block_start {
+                    // items do not end with the whitespace before them
+                    self.trim_break();
+                    self.buf.push(b'\n');
+                    self.block_start = true;
+                }
+
+                inherit_classes(ctx, &mut tag);
+
+                self.skip_ws = true;
+                if tag.name == TagName::Li {
+                    // the marker is written in the style of the list
+                    self.open_item();
+                    self.write_and_save(ctx, &tag);
+                } else {
+                    self.write_and_save(ctx, &tag);
+                    self.open_list(&tag)?;
+                }
             }
             TagName::Root => {}
         }
 
         Ok(())
     }
+
+    /// Starts a link to `href`, as an OSC 8 hyperlink or an `<a>` element.
+    fn write_link_start(&mut self, href: &[u8]) {
+        if self.format == Format::Html {
+            self.buf.extend_from_slice(b"<a href=\"");
+            html::escape(href, &mut self.buf);
+            self.buf.extend_from_slice(b"\">");
+        } else {
+            self.buf.extend_from_slice(b"\x1b]8;;");
+            self.buf.extend_from_slice(href);
+            self.buf.extend_from_slice(b"\x1b\\");
+        }
+    }
+
+    /// Ends the link started by [`Renderer::write_link_start`].
+    fn write_link_end(&mut self) {
+        if self.format == Format::Html {
+            self.buf.extend_from_slice(b"</a>");
+        } else {
+            self.buf.extend_from_slice(b"\x1b]8;;\x1b\\");
+        }
+    }
+}
+
+/// Inherits the default style of a heading, or the binding named after it if there is one.
+fn inherit_heading<'src, I: ?Sized + Input>(ctx: &Context<'src, I>, tag: &mut Tag<'src, I>) {
+    let name: &[u8] = match tag.name {
+        TagName::H1 => b"h1",
+        TagName::H2 => b"h2",
+        TagName::H3 => b"h3",
+        _ => return,
+    };
+
+    match ctx.binding(name) {
+        Some(style) => tag.inherit(style),
+        None => {
+            if let Some(style) = BUILTIN_STYLES.get(name) {
+                tag.inherit(style);
+            }
+        }
+    }
+}
+
+/// Inherits the styles of the builtin styles and bindings named in the `class` of a tag.
+fn inherit_classes<'src, I: ?Sized + Input>(ctx: &Context<'src, I>, tag: &mut Tag<'src, I>) {
+    if let Value::Some(s) = tag.class {
+        for class in s
+            .as_ref()
+            .split(|n| *n == b' ')
+            .filter(|s| !s.is_empty())
+            .rev()
+        {
+            if let Some(btag) = BUILTIN_STYLES.get(class) {
+                tag.inherit(btag);
+            } else if let Some(btag) = ctx.binding(class) {
+                tag.inherit(btag);
+            }
+        }
+    }
 }
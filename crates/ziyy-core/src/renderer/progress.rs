@@ -0,0 +1,110 @@
+use crate::context::Context;
+use crate::error::{Error, ErrorKind, Result};
+use crate::num::input_to_size;
+use crate::parser::Tag;
+use crate::shared::{Input, Span, Value};
+use crate::style::{Color, Style};
+
+use super::Renderer;
+use super::wrap::display_width;
+
+/// Width of a progress bar without a `width` attribute.
+const PROGRESS_WIDTH: usize = 20;
+
+/// Reads the `value` of a progress bar, a fraction from 0 to 1 or a percentage.
+fn parse_value<'src, I: ?Sized + Input>(tag: &Tag<'src, I>) -> Result<'src, I, f64> {
+    let Value::Some(val) = tag.custom else {
+        return Ok(0.0);
+    };
+    let invalid = || Error {
+        kind: ErrorKind::InvalidProgress(val),
+        span: tag.spans.custom,
+    };
+
+    let text = str::from_utf8(val.as_ref()).map_err(|_| invalid())?.trim();
+    let value = match text.strip_suffix('%') {
+        Some(percent) => percent.trim_end().parse::<f64>().map(|n| n / 100.0),
+        None => text.parse::<f64>(),
+    }
+    .map_err(|_| invalid())?;
+    if value.is_nan() {
+        return Err(invalid());
+    }
+
+    Ok(value.clamp(0.0, 1.0))
+}
+
+/// Returns the style of a part of a progress bar, from its color attribute or the binding
+/// named `binding`.
+fn part_style<'src, I: ?Sized + Input>(
+    ctx: &Context<'src, I>,
+    color: &Value<'src, I>,
+    span: Span,
+    binding: &[u8],
+) -> Result<'src, I, Style> {
+    let mut style = Style::new();
+    match *color {
+        Value::Some(val) => style.set_fg_color(Color::parse(val, span)?),
+        _ => {
+            if let Some(binding) = ctx.binding(binding) {
+                style = *binding;
+            }
+        }
+    }
+    Ok(style)
+}
+
+impl<O> Renderer<O> {
+    /// Writes a `<progress/>` bar.
+    ///
+    /// The filled part is drawn with `fill` in the `fill-color` or the style of the
+    /// `progress-fill` binding, the rest with `empty` in the `empty-color` or the style of
+    /// the `progress-empty` binding. Both parts are drawn over the style of the tag.
+    pub(super) fn write_progress<'src, I: ?Sized + Input>(
+        &mut self,
+        ctx: &Context<'src, I>,
+        tag: &Tag<'src, I>,
+    ) -> Result<'src, I, ()> {
+        let value = parse_value(tag)?;
+        let width = match tag.width {
+            Value::Some(val) => get_num2!(input_to_size(val), tag.spans.width),
+            _ => PROGRESS_WIDTH,
+        };
+        let char = |value: &Value<'src, I>, default: &'static str| -> Vec<u8> {
+            match *value {
+                Value::Some(val) if display_width(val.as_ref()) > 0 => val.as_ref().to_vec(),
+                _ => default.as_bytes().to_vec(),
+            }
+        };
+        let (fill, empty) = (char(&tag.fill, "█"), char(&tag.empty, "░"));
+
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+        let filled = (value * width as f64).round() as usize;
+
+        let parent = ctx.state.current();
+        let style = parent + tag.style;
+        let fill_style =
+            style + part_style(ctx, &tag.fill_color, tag.spans.fill_color, b"progress-fill")?;
+        let empty_style = style
+            + part_style(
+                ctx,
+                &tag.empty_color,
+                tag.spans.empty_color,
+                b"progress-empty",
+            )?;
+
+        self.write_revert(fill_style, parent);
+        self.write_text(&fill.repeat(filled / display_width(&fill)));
+        self.write_revert(empty_style, fill_style);
+        self.write_text(&empty.repeat((width - filled) / display_width(&empty)));
+        self.write_revert(parent, empty_style);
+
+        self.skip_ws = false;
+        self.block_start = false;
+        Ok(())
+    }
+}
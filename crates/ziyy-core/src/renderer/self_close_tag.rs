@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use crate::context::Context;
 use crate::error::Result;
-use crate::num::input_to_u8;
+use crate::num::input_to_size;
 use crate::parser::{Tag, TagName};
 use crate::shared::{Input, Value};
 
 use super::Renderer;
+use super::wrap::display_width;
 
 impl<O> Renderer<O> {
     pub(super) fn render_self_close_tag<'src, I: ?Sized + Input>(
@@ -17,15 +18,16 @@ impl<O> Renderer<O> {
         match tag.name {
             TagName::Br => {
                 if let Value::Some(val) = tag.custom {
-                    let n: usize = get_num2!(input_to_u8(val, 10), tag) as usize;
-                    for _ in 0..n {
-                        self.buf.push(b'\n');
-                    }
+                    let n = get_num2!(input_to_size(val), tag.spans.custom);
+                    self.buf.resize(self.buf.len() + n, b'\n');
                 } else {
                     self.buf.push(b'\n');
                 }
             }
 
+            TagName::Hr => self.write_rule(ctx, tag),
+            TagName::Progress => self.write_progress(ctx, tag)?,
+
             #[cfg(feature = "bindings")]
             TagName::Let => {
                 if ctx.bindings.is_none() {
@@ -44,4 +46,52 @@ impl<O> Renderer<O> {
         }
         Ok(())
     }
+
+    /// Writes a horizontal rule on its own line, as wide as the content of the open blocks
+    /// in the output, or 80 columns if no width is set.
+    ///
+    /// The rule is drawn with the `char` of the tag, `─` by default, and its `title` is
+    /// written at its center.
+    fn write_rule<'src, I: ?Sized + Input>(&mut self, ctx: &Context<'src, I>, tag: &Tag<'src, I>) {
+        if !self.block_start {
+            self.trim_break();
+            self.buf.push(b'\n');
+        }
+
+        // without a width, the rule is as wide as a default terminal
+        let width = self.content_width(self.width).unwrap_or(80);
+        let fill = match tag.custom {
+            Value::Some(val) if display_width(val.as_ref()) > 0 => val.as_ref(),
+            _ => "─".as_bytes(),
+        };
+        let fill_width = display_width(fill);
+        let title = match tag.title {
+            Value::Some(val) => val.as_ref(),
+            _ => b"",
+        };
+        let title_width = display_width(title);
+
+        let parent = ctx.state.current();
+        let style = parent + tag.style;
+        self.write_revert(style, parent);
+        if title_width == 0 {
+            self.write_text(&fill.repeat(width / fill_width));
+        } else {
+            // the title is written between spaces
+            let space = width.saturating_sub(title_width + 2);
+            let left = space / 2 / fill_width;
+            let right = (space - left * fill_width) / fill_width;
+            self.write_text(&fill.repeat(left));
+            self.buf.push(b' ');
+            self.write_text(title);
+            self.buf.push(b' ');
+            self.write_text(&fill.repeat(right));
+        }
+        self.write_revert(parent, style);
+        self.buf.push(b'\n');
+
+        self.skip_ws = true;
+        self.block_start = true;
+        self.wrap_at = None;
+    }
 }
@@ -0,0 +1,249 @@
+use crate::context::Context;
+use crate::error::Result;
+use crate::parser::Tag;
+use crate::shared::Input;
+use crate::style::Style;
+
+use super::border::Border;
+use super::layout::Align;
+use super::{Renderer, attr_or};
+
+/// A cell of a table.
+struct Cell {
+    /// Rendered content of the cell.
+    content: Vec<u8>,
+    /// Style of the parent of the cell.
+    parent: Style,
+    align: Align,
+    /// Flag to indicate whether the cell is a `<th>`.
+    header: bool,
+}
+
+/// A line of a cell.
+struct Line<'a> {
+    text: &'a [u8],
+    /// Style of the terminal at the start of the line.
+    start: Style,
+    /// Style of the terminal at the end of the line.
+    end: Style,
+    width: usize,
+}
+
+/// An open table.
+pub(super) struct Table {
+    /// Start of the table in the buffer.
+    start: usize,
+    /// Style of the parent of the table.
+    parent: Style,
+    /// Style of the table, borders are drawn with it.
+    style: Style,
+    border: Option<&'static Border>,
+    rows: Vec<Vec<Cell>>,
+    /// Start of the open cell in the buffer, with the style of its parent and its alignment.
+    cell: Option<(usize, Style, Align)>,
+}
+
+impl<O> Renderer<O> {
+    /// Opens a table. `start` is where the table starts in the buffer and `parent` is the
+    /// style of its parent.
+    pub(super) fn open_table<'src, I: ?Sized + Input>(
+        &mut self,
+        ctx: &Context<'src, I>,
+        tag: &Tag<'src, I>,
+        start: usize,
+        parent: Style,
+    ) -> Result<'src, I, ()> {
+        let mut error = None;
+        self.tables.push(Table {
+            start,
+            parent,
+            style: ctx.state.current(),
+            border: attr_or(Border::parse(&tag.custom, tag), None, &mut error),
+            rows: Vec::new(),
+            cell: None,
+        });
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Returns whether the buffer is in the innermost table but outside of its cells.
+    pub(super) fn outside_cell(&self) -> bool {
+        self.tables.last().is_some_and(|table| table.cell.is_none())
+    }
+
+    pub(super) fn open_row(&mut self) {
+        if let Some(table) = self.tables.last_mut() {
+            table.rows.push(Vec::new());
+        }
+    }
+
+    /// Opens a cell of the innermost table. `start` is where the cell starts in the buffer
+    /// and `parent` is the style of its parent.
+    pub(super) fn open_cell<'src, I: ?Sized + Input>(
+        &mut self,
+        tag: &Tag<'src, I>,
+        start: usize,
+        parent: Style,
+    ) -> Result<'src, I, ()> {
+        let mut error = None;
+        let align = attr_or(Align::parse(tag), Align::Left, &mut error);
+        if let Some(table) = self.tables.last_mut() {
+            table.cell = Some((start, parent, align));
+        }
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Moves the rendered content of the open cell out of the buffer.
+    pub(super) fn close_cell(&mut self, header: bool) {
+        let Some(table) = self.tables.last_mut() else {
+            return;
+        };
+        let Some((start, parent, align)) = table.cell.take() else {
+            return;
+        };
+
+        let content = self.buf.drain(start..).collect();
+        if table.rows.is_empty() {
+            table.rows.push(Vec::new());
+        }
+        if let Some(row) = table.rows.last_mut() {
+            row.push(Cell {
+                content,
+                parent,
+                align,
+                header,
+            });
+        }
+    }
+
+    /// Closes the innermost table, laying out its cells in columns.
+    ///
+    /// Columns are as wide as their widest line, the lines of cells are not wrapped. Each
+    /// line of a cell is written in the style it was rendered with and the style of the
+    /// table is restored after it, so styles do not leak into other columns or the border.
+    /// Rows without cells are skipped and a table without cells is not drawn.
+    pub(super) fn close_table(&mut self) {
+        let Some(mut table) = self.tables.pop() else {
+            return;
+        };
+        self.buf.truncate(table.start);
+        table.rows.retain(|row| !row.is_empty());
+        if table.rows.is_empty() {
+            self.wrap_at = None;
+            return;
+        }
+
+        let rows: Vec<Vec<Vec<Line>>> = table
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| self.lines(cell)).collect())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let mut widths = vec![0; columns];
+        for row in &rows {
+            for (width, lines) in widths.iter_mut().zip(row) {
+                for line in lines {
+                    *width = line.width.max(*width);
+                }
+            }
+        }
+        let padded: Vec<usize> = widths.iter().map(|width| width + 2).collect();
+
+        self.write_revert(table.style, table.parent);
+        if let Some(border) = table.border {
+            self.buf
+                .extend_from_slice(border.line(0, &padded).as_bytes());
+            self.buf.push(b'\n');
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            let height = row.iter().map(Vec::len).max().unwrap_or(1);
+            for k in 0..height {
+                if let Some(border) = table.border {
+                    self.buf.extend_from_slice(border.v.as_bytes());
+                    self.buf.push(b' ');
+                }
+
+                for (j, width) in widths.iter().enumerate() {
+                    // without a border, lines end after their last cell
+                    let last = table.border.is_none() && j + 1 >= row.len();
+                    if table.border.is_none() && j >= row.len() {
+                        break;
+                    }
+                    if j > 0 {
+                        match table.border {
+                            Some(border) => {
+                                self.buf.push(b' ');
+                                self.buf.extend_from_slice(border.v.as_bytes());
+                                self.buf.push(b' ');
+                            }
+                            None => self.write_spaces(2),
+                        }
+                    }
+
+                    match row.get(j).and_then(|lines| lines.get(k)) {
+                        Some(line) => {
+                            let space = width - line.width;
+                            let left = table.rows[i][j].align.offset(space);
+                            self.write_revert(line.start, table.style);
+                            self.write_spaces(left);
+                            self.buf.extend_from_slice(line.text);
+                            if !last {
+                                self.write_spaces(space - left);
+                            }
+                            self.write_revert(table.style, line.end);
+                        }
+                        None if last => {}
+                        None => self.write_spaces(*width),
+                    }
+                }
+
+                if let Some(border) = table.border {
+                    self.buf.push(b' ');
+                    self.buf.extend_from_slice(border.v.as_bytes());
+                }
+                self.buf.push(b'\n');
+            }
+
+            // headers are separated from the rows below them
+            if let Some(border) = table.border
+                && i + 1 < rows.len()
+                && table.rows[i].iter().any(|cell| cell.header)
+            {
+                self.buf
+                    .extend_from_slice(border.line(1, &padded).as_bytes());
+                self.buf.push(b'\n');
+            }
+        }
+
+        match table.border {
+            Some(border) => self
+                .buf
+                .extend_from_slice(border.line(2, &padded).as_bytes()),
+            None => {
+                if self.buf.last() == Some(&b'\n') {
+                    self.buf.pop();
+                }
+            }
+        }
+        self.write_revert(table.parent, table.style);
+        self.wrap_at = None;
+    }
+
+    /// Splits the content of a cell in lines.
+    fn lines<'a>(&self, cell: &'a Cell) -> Vec<Line<'a>> {
+        let mut style = cell.parent;
+        cell.content
+            .split(|b| *b == b'\n')
+            .map(|text| {
+                let start = style;
+                style = self.style_after(style, text);
+                Line {
+                    text,
+                    start,
+                    end: style,
+                    width: self.width(text),
+                }
+            })
+            .collect()
+    }
+}
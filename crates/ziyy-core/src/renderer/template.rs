@@ -0,0 +1,87 @@
+use std::fmt::Display;
+use std::slice;
+
+use crate::context::Context;
+use crate::error::{Error, ErrorKind, Result};
+use crate::parser::{Chunk, Parser};
+use crate::shared::{Input, Position, Span};
+
+use super::Renderer;
+
+/// Arguments of a template, written in place of its `{}` placeholders.
+pub(crate) struct Args<'a> {
+    values: slice::Iter<'a, &'a dyn Display>,
+}
+
+impl<'a> Args<'a> {
+    pub(super) fn new(values: &'a [&'a dyn Display]) -> Self {
+        Self {
+            values: values.iter(),
+        }
+    }
+}
+
+impl<O> Renderer<O> {
+    /// Writes text of a template, replacing each `{}` with the next argument. `{{` and `}}`
+    /// are written as `{` and `}`.
+    ///
+    /// Arguments are written as text and never parsed, and their control characters are
+    /// dropped, so they can not open tags or write escape sequences.
+    pub(super) fn write_template<'src, I: ?Sized + Input>(
+        &mut self,
+        ctx: &mut Context<'src, I>,
+        text: &'src I,
+        span: Span,
+        args: &mut Args,
+    ) -> Result<'src, I, ()> {
+        let bytes = text.as_ref();
+        let mut i = 0;
+        while i < bytes.len() {
+            match (bytes[i], bytes.get(i + 1)) {
+                (b'{', Some(b'}')) => {
+                    let Some(arg) = args.values.next() else {
+                        return Err(Error {
+                            kind: ErrorKind::MissingArgument,
+                            span,
+                        });
+                    };
+                    self.write_arg(&arg.to_string());
+                    i += 2;
+                }
+                (b'{', Some(b'{')) | (b'}', Some(b'}')) => {
+                    self.buf.push(bytes[i]);
+                    i += 2;
+                }
+                (b'{', None) => {
+                    // the scanner ends text before a `{`, so `{{` is split in two texts
+                    if let Chunk::Text(next, next_span) = Parser::parse_next(ctx)?
+                        && next.as_ref().first() == Some(&b'{')
+                    {
+                        let start = Position::new(next_span.start.row, next_span.start.col + 1);
+                        ctx.next_chunk =
+                            Some(Chunk::Text(&next[1..], Span::new(start, next_span.end)));
+                    }
+                    self.buf.push(b'{');
+                    i += 1;
+                }
+                _ => {
+                    self.write_text(&bytes[i..=i]);
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes an argument of a template, dropping control characters other than newlines
+    /// and tabs.
+    fn write_arg(&mut self, arg: &str) {
+        for c in arg.chars() {
+            if !c.is_control() || matches!(c, '\n' | '\t') {
+                let mut bytes = [0; 4];
+                self.write_text(c.encode_utf8(&mut bytes).as_bytes());
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use smallvec::SmallVec;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ansi::{Segment, Segments};
+use crate::style::Style;
+
+use super::{Format, Renderer, html};
+
+/// Whitespace a line can be broken at.
+#[derive(Clone, Copy)]
+pub(super) struct Break {
+    /// Start of the whitespace in the buffer.
+    start: usize,
+    /// End of the whitespace in the buffer.
+    end: usize,
+    /// Style the whitespace is written with.
+    style: Style,
+}
+
+/// Returns the number of columns `text` takes in a terminal.
+///
+/// Widths follow the Unicode rules for East Asian characters and zero-width characters.
+/// Escape sequences take no columns.
+pub(crate) fn display_width(text: &[u8]) -> usize {
+    width(Segments::new(text))
+}
+
+/// Returns the number of columns the text of `segments` takes in a terminal.
+fn width<'a>(segments: impl Iterator<Item = Segment<'a>>) -> usize {
+    segments
+        .map(|segment| match segment {
+            Segment::Text(text) => String::from_utf8_lossy(text).width(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// An iterator over the [`Segment`]s of rendered text, whose styles are escape sequences
+/// or HTML elements depending on the format.
+pub(super) enum Rendered<'a, 's> {
+    Ansi(Segments<'a>),
+    Html(html::Segments<'a, 's>),
+}
+
+impl Rendered<'_, '_> {
+    /// Returns the offset in the text after the last returned segment.
+    pub(super) fn offset(&self) -> usize {
+        match self {
+            Rendered::Ansi(segments) => segments.offset(),
+            Rendered::Html(segments) => segments.offset(),
+        }
+    }
+}
+
+impl<'a> Iterator for Rendered<'a, '_> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Rendered::Ansi(segments) => segments.next(),
+            Rendered::Html(segments) => segments.next(),
+        }
+    }
+}
+
+impl<O> Renderer<O> {
+    /// Returns the segments of `text` written by the renderer.
+    pub(super) fn segments<'a>(&self, text: &'a [u8]) -> Rendered<'a, '_> {
+        match self.format {
+            Format::Html => Rendered::Html(html::Segments::new(text, &self.html_styles)),
+            _ => Rendered::Ansi(Segments::new(text)),
+        }
+    }
+
+    /// Returns the number of columns `text` written by the renderer takes, like
+    /// [`display_width`].
+    pub(super) fn width(&self, text: &[u8]) -> usize {
+        width(self.segments(text))
+    }
+
+    /// Returns the width lines are wrapped at, `None` if lines are not wrapped.
+    fn wrap_width(&self) -> Option<usize> {
+        // cells are laid out when their table is closed
+        if !self.tables.is_empty() || self.blocks.last().is_some_and(|block| block.pre) {
+            return None;
+        }
+
+        self.content_width(self.width)
+    }
+
+    /// Writes whitespace that a line can be broken at.
+    pub(super) fn write_break(&mut self, ws: &[u8], style: Style) {
+        let start = self.buf.len();
+        self.buf.extend_from_slice(ws);
+        self.wrap_at = Some(Break {
+            start,
+            end: self.buf.len(),
+            style,
+        });
+    }
+
+    /// Removes whitespace that a line could be broken at from the end of the buffer.
+    pub(super) fn trim_break(&mut self) {
+        if let Some(at) = self.wrap_at.take()
+            && at.end == self.buf.len()
+        {
+            self.buf.truncate(at.start);
+        }
+    }
+
+    /// Breaks the current line at the last whitespace if it is wider than the wrap width.
+    ///
+    /// The style of the line is unset before the inserted newline and set again after it, so
+    /// backgrounds and underlines stop at the end of the line.
+    pub(super) fn wrap(&mut self) {
+        let Some(width) = self.wrap_width() else {
+            return;
+        };
+        let Some(at) = self.wrap_at else {
+            return;
+        };
+
+        let line = self
+            .buf
+            .iter()
+            .rposition(|b| *b == b'\n')
+            .map_or(0, |n| n + 1);
+        if at.start < line {
+            self.wrap_at = None;
+            return;
+        }
+        if self.width(&self.buf[line..]) <= width {
+            return;
+        }
+
+        let tail: SmallVec<[u8; 64]> = self.buf.drain(at.end..).collect();
+        self.buf.truncate(at.start);
+        self.write_revert(Style::new(), at.style);
+        self.buf.push(b'\n');
+        // lines of list items hang under the text of the item
+        let hang = self.hang();
+        self.write_spaces(hang);
+        self.write_style(at.style - Style::new(), Style::new(), at.style);
+        self.buf.extend_from_slice(&tail);
+        self.wrap_at = None;
+    }
+}
@@ -18,6 +18,8 @@ pub struct Scanner<'src, I: ?Sized + Input> {
     start: u32,
     current: u32,
     pub(crate) text_mode: bool,
+    /// Treat `<` and `>` as text, so that only escape sequences are recognized.
+    pub(crate) escapes_only: bool,
     pub start_pos: Position,
     pub current_pos: Position,
 }
@@ -38,6 +40,7 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
             start: 0,
             current: 0,
             text_mode: true,
+            escapes_only: false,
             start_pos: Position::new(1, 1),
             current_pos: Position::new(1, 1),
         }
@@ -63,11 +66,32 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
         let ch = self.source.as_ref()[self.current as usize - 1] as char;
         if ch == '\n' {
             self.current_pos.row += 1;
-            self.current_pos.col = 0;
+            self.current_pos.col = 1;
         }
         ch
     }
 
+    /// Skips the rest of a malformed tag, up to and including the next `>`.
+    ///
+    /// Returns `true` if the tag ended with `/>`.
+    pub(crate) fn skip_tag(&mut self) -> bool {
+        let mut prev = '\0';
+        while !self.text_mode {
+            if self.is_at_end() {
+                self.text_mode = true;
+            } else {
+                let c = self.advance();
+                self.text_mode = c == '>';
+                if !self.text_mode {
+                    prev = c;
+                }
+            }
+        }
+        self.start = self.current;
+        self.start_pos = self.current_pos;
+        prev == '/'
+    }
+
     pub fn advance_n(&mut self, n: u32) {
         self.current += n;
         self.current_pos.col += n;
@@ -145,9 +169,15 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
         if c == '\\' {
             return self.escape();
         }
+        if c == '\x1b' && self.peek(0) == '[' {
+            self.advance_n(1);
+            return self.ansi_sgr(false);
+        }
         while !self.is_at_end() {
             match self.peek(0) {
-                '<' | '\\' | '{' | '>' => break,
+                '<' | '>' if !self.escapes_only => break,
+                '\\' | '{' => break,
+                '\x1b' if self.peek(1) == '[' => break,
                 ws if is_whitespace(ws) => break,
                 _ => {
                     self.advance_n(1);
@@ -205,9 +235,12 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
     #[allow(clippy::too_many_lines)]
     pub fn identifier_kind(&mut self) -> TokenKind {
         use token::TokenKind::{
-            A, B, BLACK, BLUE, BR, C, CLASS, CURLY, CYAN, D, DIV, DOTTED, DOUBLE, FIXED, GREEN, H,
-            HREF, I, ID, IDENTIFIER, INDENT, K, LET, LIGHT, MAGENTA, N, NONE, P, PRE, R, RED, RGB,
-            S, SINGLE, SPAN, U, UU, WHITE, X, YELLOW, ZIYY,
+            A, ALIGN, B, BLACK, BLUE, BORDER, BORDER_COLOR, BOX, BR, BULLET, C, CHAR, CLASS, CURLY,
+            CYAN, D, DASHED, DIV, DOTTED, DOUBLE, EMPTY, EMPTY_COLOR, ENCIRCLED, FAST, FILL,
+            FILL_COLOR, FIXED, FONT, FRAKTUR, FRAMED, FROM, GRADIENT, GREEN, H, H1, H2, H3, HR,
+            HREF, I, ID, IDENTIFIER, INDENT, K, LET, LI, LIGHT, MAGENTA, MARGIN, N, NONE, O, OL, P,
+            PAD, PRE, PROGRESS, PROPORTIONAL, R, RED, RGB, S, SINGLE, SPAN, START, STOPS, TABLE,
+            TD, TH, TITLE, TO, TR, U, UL, UU, VALUE, WHITE, WIDTH, X, YELLOW, ZIYY,
         };
 
         macro_rules! get {
@@ -263,7 +296,9 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
         }
 
         match_ident!({
-            'a': A,
+            'a': (A {
+                'l': ("ign", ALIGN),
+            }),
             'b': (B {
                 'g': X,
                 'l': {
@@ -271,15 +306,29 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
                     'i': ("nk", K),
                     'u': ("e", BLUE),
                 },
+                'o': {
+                    'r': {
+                        'd': {
+                            'e': {
+                                'r': (BORDER {
+                                    '-': ("color", BORDER_COLOR),
+                                }),
+                            },
+                        },
+                    },
+                    'x': BOX,
+                },
+                'u': ("llet", BULLET),
                 'r': BR,
             }),
             'c': (C {
+                'h': ("ar", CHAR),
                 'l': ("ass", CLASS),
                 'u': ("rly", CURLY),
                 'y': ("an", CYAN),
             }),
             'd': (D {
-                'a': ("shed", CYAN),
+                'a': ("shed", DASHED),
                 'i': {
                     'm': D,
                     'v': DIV,
@@ -309,20 +358,57 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
                     },
                 },
             }),
-            'e': ("m", I),
+            'e': {
+                'm': (I {
+                    'p': {
+                        't': {
+                            'y': (EMPTY {
+                                '-': ("color", EMPTY_COLOR),
+                            }),
+                        },
+                    },
+                }),
+                'n': ("circled", ENCIRCLED),
+            },
             'f': {
-                'i': ("xed", FIXED),
+                'i': {
+                    'l': {
+                        'l': (FILL {
+                            '-': ("color", FILL_COLOR),
+                        }),
+                    },
+                    'x': ("ed", FIXED),
+                },
                 'g': C,
+                'a': ("st", FAST),
+                'o': ("nt", FONT),
+                'r': {
+                    'a': {
+                        'k': ("tur", FRAKTUR),
+                        'm': ("ed", FRAMED),
+                    },
+                    'o': ("m", FROM),
+                },
+            },
+            'g': {
+                'r': {
+                    'a': ("dient", GRADIENT),
+                    'e': ("en", GREEN),
+                },
             },
-            'g': ("reen", GREEN),
             'h': (H {
+                '1': H1,
+                '2': H2,
+                '3': H3,
                 'i': {
                     'd': {
                         'd': ("en", H),
                         'e': H,
                     },
                 },
-                'r': ("ef", HREF),
+                'r': (HR {
+                    'e': ("f", HREF),
+                }),
             }),
             'i': (I {
                 'd': ID,
@@ -339,15 +425,33 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
             'k': K,
             'l': {
                 'e': ("t", LET),
-                'i': ("ght", LIGHT),
+                'i': (LI {
+                    'g': ("ht", LIGHT),
+                }),
+            },
+            'm': {
+                'a': {
+                    'g': ("enta", MAGENTA),
+                    'r': ("gin", MARGIN),
+                },
             },
-            'm': ("agenta", MAGENTA),
             'n': (N {
                 'e': ("gative", R),
                 'o': ("ne", NONE),
             }),
+            'o': (O {
+                'l': OL,
+                'v': ("erline", O),
+            }),
             'p': (P {
-                'r': ("e", PRE),
+                'a': ("d", PAD),
+                'r': {
+                    'e': PRE,
+                    'o': {
+                        'g': ("ress", PROGRESS),
+                        'p': ("ortional", PROPORTIONAL),
+                    },
+                },
             }),
             'r': (R {
                 'e': {
@@ -360,6 +464,8 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
                 'i': ("ngle", SINGLE),
                 'p': ("an", SPAN),
                 't': {
+                    'a': ("rt", START),
+                    'o': ("ps", STOPS),
                     'r': {
                         'i': {
                             'k': {
@@ -371,7 +477,16 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
                     },
                 },
             }),
+            't': {
+                'a': ("ble", TABLE),
+                'd': TD,
+                'h': TH,
+                'i': ("tle", TITLE),
+                'o': TO,
+                'r': TR,
+            },
             'u': (U {
+                'l': UL,
                 'n': {
                     'd': {
                         'e': {
@@ -383,7 +498,11 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
                 },
                 'u': UU,
             }),
-            'w': ("hite", WHITE),
+            'v': ("alue", VALUE),
+            'w': {
+                'h': ("ite", WHITE),
+                'i': ("dth", WIDTH),
+            },
             'x': X,
             'y': ("ellow", YELLOW),
             'z': (ZIYY {
@@ -530,6 +649,10 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
         let kind = match c {
             'a' => TokenKind::ESC_A,
             'b' => TokenKind::ESC_B,
+            'e' if self.peek(0) == '[' => {
+                self.advance_n(1);
+                return self.ansi_sgr(true);
+            }
             'e' => TokenKind::ESC_E,
             'f' => TokenKind::ESC_F,
             'n' => TokenKind::ESC_N,
@@ -582,7 +705,7 @@ impl<'src, I: ?Sized + Input> Scanner<'src, I> {
 
         let c = self.advance();
 
-        if self.text_mode && !matches!(c, '<' | '>') {
+        if self.text_mode && (self.escapes_only || !matches!(c, '<' | '>')) {
             return self.text_token(c);
         }
 
@@ -726,6 +849,16 @@ mod tests {
         assert_eq!(scanner.advance(), 'c');
     }
 
+    #[test]
+    fn test_advance_resets_column_after_newline() {
+        let mut scanner = Scanner::new("a\nb");
+        scanner.advance();
+        scanner.advance();
+        assert_eq!(scanner.current_pos, Position::new(2, 1));
+        scanner.advance();
+        assert_eq!(scanner.current_pos, Position::new(2, 2));
+    }
+
     #[test]
     fn test_peek() {
         let mut scanner = Scanner::new("abc");
@@ -809,6 +942,19 @@ mod tests {
         assert_eq!(token.kind, TokenKind::GREAT);
     }
 
+    #[test]
+    fn test_scan_token_escapes_only() {
+        let mut scanner = Scanner::new("<b>\\n");
+        scanner.escapes_only = true;
+        let token = scanner.scan_token().unwrap();
+        assert_eq!(token.content, "<b>");
+        assert_eq!(token.kind, TokenKind::TEXT);
+
+        let token = scanner.scan_token().unwrap();
+        assert_eq!(token.content, "\\n");
+        assert_eq!(token.kind, TokenKind::ESC_N);
+    }
+
     #[test]
     fn test_skip_whitespace() {
         let mut scanner = Scanner::new("   abc");
@@ -60,40 +60,78 @@ pub enum TokenKind {
     // Tag Names
     A,
     B,
+    BOX,
     BR,
     C,
     CODE,
     D,
     DIV,
+    GRADIENT,
     H,
+    H1,
+    H2,
+    H3,
+    HR,
     I,
     K,
     LET,
+    LI,
+    OL,
     O,
     P,
     PRE,
+    PROGRESS,
     R,
     S,
     SPAN,
+    TABLE,
+    TD,
+    TH,
+    TR,
     U,
     UU,
     X,
     ZIYY,
 
     // Others
+    ALIGN,
+    BORDER,
+    BORDER_COLOR,
+    BULLET,
+    CHAR,
     CLASS,
     CURLY,
     BLOCK,
     DASHED,
     DOUBLE,
     DOTTED,
+    EMPTY,
+    EMPTY_COLOR,
+    ENCIRCLED,
+    FAST,
+    FILL,
+    FILL_COLOR,
+    FONT,
+    FRAKTUR,
+    FRAMED,
+    FROM,
     ID,
     INDENT,
     LIGHT,
+    MARGIN,
     HREF,
     N,
     NONE,
+    PAD,
+    PROPORTIONAL,
     SINGLE,
+    START,
+    STOPS,
+    TITLE,
+    TO,
+    UL,
+    VALUE,
+    WIDTH,
 
     // Special
     COMMENT,
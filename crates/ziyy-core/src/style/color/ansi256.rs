@@ -1,4 +1,4 @@
-use super::ColorKind;
+use super::{AnsiColor, ColorKind, Rgb};
 
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
 pub struct Ansi256(pub u8);
@@ -10,4 +10,30 @@ impl Ansi256 {
         let Ansi256(n) = self;
         format!("\x1b[{};5;{n}m", kind as u8 + 8)
     }
+
+    /// Returns the xterm default value of this color.
+    #[must_use]
+    pub const fn to_rgb(&self) -> Rgb {
+        const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+        let Ansi256(n) = *self;
+        match n {
+            0..16 => match AnsiColor::from_index(n).to_rgb() {
+                Some(rgb) => rgb,
+                None => unreachable!(),
+            },
+            16..232 => {
+                let n = n - 16;
+                Rgb(
+                    LEVELS[(n / 36) as usize],
+                    LEVELS[(n / 6 % 6) as usize],
+                    LEVELS[(n % 6) as usize],
+                )
+            }
+            232.. => {
+                let level = 8 + (n - 232) * 10;
+                Rgb(level, level, level)
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::ColorKind;
+use super::{ColorKind, Rgb};
 
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
 pub enum AnsiColor {
@@ -28,7 +28,79 @@ impl AnsiColor {
     #[must_use]
     #[inline]
     pub fn to_string(&self, kind: ColorKind) -> String {
-        format!("\x1b[{}m", kind as u8 + *self as u8)
+        match (kind, self) {
+            // There are no 16-color codes for underlines, the palette is used instead.
+            (ColorKind::Underline, AnsiColor::Default) => "\x1b[59m".to_string(),
+            (ColorKind::Underline, _) => format!("\x1b[58;5;{}m", self.to_index()),
+            (kind, _) => format!("\x1b[{}m", kind as u8 + *self as u8),
+        }
+    }
+
+    /// Returns the index (`0..16`) of this color in the 16-color palette.
+    pub(crate) const fn to_index(self) -> u8 {
+        match self as u8 {
+            n @ 60.. => n - 52,
+            n => n,
+        }
+    }
+
+    /// Returns the color at `index` (`0..16`) of the 16-color palette.
+    pub(crate) const fn from_index(index: u8) -> Self {
+        use AnsiColor::{
+            Black, Blue, BrightBlack, BrightBlue, BrightCyan, BrightGreen, BrightMagenta,
+            BrightRed, BrightWhite, BrightYellow, Cyan, Green, Magenta, Red, White, Yellow,
+        };
+
+        match index {
+            0 => Black,
+            1 => Red,
+            2 => Green,
+            3 => Yellow,
+            4 => Blue,
+            5 => Magenta,
+            6 => Cyan,
+            7 => White,
+            8 => BrightBlack,
+            9 => BrightRed,
+            10 => BrightGreen,
+            11 => BrightYellow,
+            12 => BrightBlue,
+            13 => BrightMagenta,
+            14 => BrightCyan,
+            15 => BrightWhite,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the xterm default value of this color,
+    /// or `None` for the terminal's default color.
+    #[must_use]
+    pub const fn to_rgb(&self) -> Option<Rgb> {
+        use AnsiColor::{
+            Black, Blue, BrightBlack, BrightBlue, BrightCyan, BrightGreen, BrightMagenta,
+            BrightRed, BrightWhite, BrightYellow, Cyan, Default, Green, Magenta, Red, White,
+            Yellow,
+        };
+
+        Some(match self {
+            Black => Rgb(0, 0, 0),
+            Red => Rgb(205, 0, 0),
+            Green => Rgb(0, 205, 0),
+            Yellow => Rgb(205, 205, 0),
+            Blue => Rgb(0, 0, 238),
+            Magenta => Rgb(205, 0, 205),
+            Cyan => Rgb(0, 205, 205),
+            White => Rgb(229, 229, 229),
+            Default => return None,
+            BrightBlack => Rgb(127, 127, 127),
+            BrightRed => Rgb(255, 0, 0),
+            BrightGreen => Rgb(0, 255, 0),
+            BrightYellow => Rgb(255, 255, 0),
+            BrightBlue => Rgb(92, 92, 255),
+            BrightMagenta => Rgb(255, 0, 255),
+            BrightCyan => Rgb(0, 255, 255),
+            BrightWhite => Rgb(255, 255, 255),
+        })
     }
 }
 
@@ -0,0 +1,145 @@
+use std::env;
+use std::ops::RangeInclusive;
+
+#[cfg(feature = "terminfo")]
+use terminfo::capability::{MaxColors, TrueColor};
+
+#[cfg(feature = "terminfo")]
+use crate::context::DATABASE;
+
+use super::{Ansi256, AnsiColor, Color, Rgb};
+
+/// Number of colors a terminal can show.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// No colors, only effects are written.
+    None,
+    /// The 16 ANSI colors.
+    Ansi16,
+    /// The 256 colors palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    #[default]
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Detects the color depth of the terminal.
+    ///
+    /// `COLORTERM` is checked first, then the terminfo database of the terminal (with the
+    /// `terminfo` feature) and finally `TERM`.
+    #[must_use]
+    pub fn detect() -> Self {
+        if let Ok(colorterm) = env::var("COLORTERM")
+            && matches!(colorterm.as_str(), "truecolor" | "24bit")
+        {
+            return ColorDepth::TrueColor;
+        }
+
+        #[cfg(feature = "terminfo")]
+        if let Some(db) = DATABASE.as_ref() {
+            if db.get::<TrueColor>().is_some_and(|tc| tc.0) {
+                return ColorDepth::TrueColor;
+            }
+            if let Some(MaxColors(n)) = db.get::<MaxColors>() {
+                return match n {
+                    0x0100_0000.. => ColorDepth::TrueColor,
+                    256.. => ColorDepth::Ansi256,
+                    8.. => ColorDepth::Ansi16,
+                    _ => ColorDepth::None,
+                };
+            }
+        }
+
+        match env::var("TERM") {
+            Ok(term) if term == "dumb" => ColorDepth::None,
+            Ok(term) if term.ends_with("-direct") || term.ends_with("-truecolor") => {
+                ColorDepth::TrueColor
+            }
+            Ok(term) if term.contains("256color") => ColorDepth::Ansi256,
+            Ok(_) => ColorDepth::Ansi16,
+            Err(_) => ColorDepth::None,
+        }
+    }
+}
+
+impl Color {
+    /// Returns the nearest color that can be shown with `depth`.
+    ///
+    /// Colors are compared using a weighted euclidean distance which approximates how
+    /// different they look.
+    #[must_use]
+    pub fn downsample(self, depth: ColorDepth) -> Color {
+        match (depth, self) {
+            (ColorDepth::None, _) => Color::None,
+            (_, Color::None | Color::Unset) | (ColorDepth::TrueColor, _) => self,
+
+            (ColorDepth::Ansi256, Color::Rgb(rgb)) => {
+                Color::Ansi256(Ansi256(nearest(rgb, 16..=255, |n| Ansi256(n).to_rgb())))
+            }
+            (ColorDepth::Ansi256, _) => self,
+
+            (ColorDepth::Ansi16, Color::Ansi256(Ansi256(n))) if n < 16 => {
+                Color::AnsiColor(AnsiColor::from_index(n))
+            }
+            (ColorDepth::Ansi16, Color::Rgb(_) | Color::Ansi256(_)) => match self.to_rgb() {
+                Some(rgb) => Color::AnsiColor(AnsiColor::from_index(nearest(rgb, 0..=15, |n| {
+                    AnsiColor::from_index(n).to_rgb().unwrap_or_default()
+                }))),
+                None => self,
+            },
+            (ColorDepth::Ansi16, Color::AnsiColor(_)) => self,
+        }
+    }
+}
+
+/// Returns the index in `range` of the palette color nearest to `rgb`.
+fn nearest(rgb: Rgb, range: RangeInclusive<u8>, palette: impl Fn(u8) -> Rgb) -> u8 {
+    range
+        .min_by_key(|n| distance(rgb, palette(*n)))
+        .unwrap_or_default()
+}
+
+/// The "redmean" approximation of the perceived distance between two colors.
+fn distance(Rgb(r1, g1, b1): Rgb, Rgb(r2, g2, b2): Rgb) -> i32 {
+    let rmean = i32::from(r1).midpoint(i32::from(r2));
+    let dr = i32::from(r1) - i32::from(r2);
+    let dg = i32::from(g1) - i32::from(g2);
+    let db = i32::from(b1) - i32::from(b2);
+
+    (((512 + rmean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - rmean) * db * db) >> 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downsample() {
+        let orange = Color::Rgb(Rgb(255, 135, 0));
+        assert_eq!(orange.downsample(ColorDepth::TrueColor), orange);
+        assert_eq!(
+            orange.downsample(ColorDepth::Ansi256),
+            Color::Ansi256(Ansi256(208))
+        );
+        assert_eq!(
+            orange.downsample(ColorDepth::Ansi16),
+            Color::AnsiColor(AnsiColor::Yellow)
+        );
+        assert_eq!(orange.downsample(ColorDepth::None), Color::None);
+
+        assert_eq!(
+            Color::Ansi256(Ansi256(9)).downsample(ColorDepth::Ansi16),
+            Color::AnsiColor(AnsiColor::BrightRed)
+        );
+        assert_eq!(
+            Color::Ansi256(Ansi256(232)).downsample(ColorDepth::Ansi16),
+            Color::AnsiColor(AnsiColor::Black)
+        );
+        assert_eq!(
+            Color::AnsiColor(AnsiColor::Green).downsample(ColorDepth::Ansi256),
+            Color::AnsiColor(AnsiColor::Green)
+        );
+        assert_eq!(Color::Unset.downsample(ColorDepth::Ansi16), Color::Unset);
+    }
+}
@@ -8,10 +8,12 @@ use crate::style::convert::FromU32;
 
 pub use ansi_color::AnsiColor;
 pub use ansi256::Ansi256;
+pub use depth::ColorDepth;
 pub use rgb::Rgb;
 
 mod ansi256;
 mod ansi_color;
+mod depth;
 mod rgb;
 
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
@@ -49,6 +51,19 @@ impl Color {
         self.to_string(kind).into_bytes()
     }
 
+    /// Returns the RGB value of this color, using the xterm defaults for palette colors.
+    ///
+    /// Returns `None` if the color is not set or is the terminal's default color.
+    #[must_use]
+    pub const fn to_rgb(&self) -> Option<Rgb> {
+        match self {
+            Color::Rgb(rgb) => Some(*rgb),
+            Color::Ansi256(ansi256) => Some(ansi256.to_rgb()),
+            Color::AnsiColor(ansi_color) => ansi_color.to_rgb(),
+            Color::None | Color::Unset => None,
+        }
+    }
+
     pub(crate) fn parse<'src, I: ?Sized + Input>(
         source: &'src I,
         span: Span,
@@ -265,4 +280,22 @@ mod tests {
 
         assert_eq!(Color::parse("none", Span::default()).unwrap(), Color::Unset);
     }
+
+    #[test]
+    fn test_color_to_rgb() {
+        assert_eq!(
+            Color::AnsiColor(AnsiColor::BrightRed).to_rgb(),
+            Some(Rgb(255, 0, 0))
+        );
+        assert_eq!(Color::Ansi256(Ansi256(9)).to_rgb(), Some(Rgb(255, 0, 0)));
+        assert_eq!(
+            Color::Ansi256(Ansi256(208)).to_rgb(),
+            Some(Rgb(255, 135, 0))
+        );
+        assert_eq!(
+            Color::Ansi256(Ansi256(244)).to_rgb(),
+            Some(Rgb(128, 128, 128))
+        );
+        assert_eq!(Color::Unset.to_rgb(), None);
+    }
 }
@@ -2,6 +2,12 @@ pub trait FromU8: Sized {
     fn from_u8(value: u8) -> Self;
 }
 
+impl FromU8 for bool {
+    fn from_u8(value: u8) -> Self {
+        value != 0
+    }
+}
+
 pub trait FromU32: Sized {
     fn from_u32(value: u32) -> Self;
 }
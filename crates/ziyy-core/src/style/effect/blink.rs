@@ -12,19 +12,29 @@ pub enum Blink {
 }
 
 impl Blink {
-    #[must_use]
+    /// Slow and fast blink are separate attributes on some terminals, so both are unset
+    /// before `switched` from one to the other.
     #[inline]
-    pub const fn as_str(&self) -> &str {
+    pub(in crate::style) const fn as_str2(&self, switched: bool) -> &str {
         use Blink::{Fast, None, Slow, Unset};
 
-        match self {
-            None => "",
-            Slow => "\x1b[5m",
-            Fast => "\x1b[6m",
-            Unset => "\x1b[25m",
+        match (switched, self) {
+            (true, Slow) => "\x1b[25;5m",
+            (true, Fast) => "\x1b[25;6m",
+
+            (_, None) => "",
+            (_, Slow) => "\x1b[5m",
+            (_, Fast) => "\x1b[6m",
+            (_, Unset) => "\x1b[25m",
         }
     }
 
+    #[must_use]
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        self.as_str2(false)
+    }
+
     #[must_use]
     #[inline]
     pub const fn as_bytes(&self) -> &[u8] {
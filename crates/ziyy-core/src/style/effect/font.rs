@@ -47,6 +47,11 @@ impl Font {
     pub const fn as_bytes(&self) -> &[u8] {
         self.as_str().as_bytes()
     }
+
+    /// Returns the primary font for `0` and an alternative font for `1..=9`.
+    pub(crate) fn from_number(n: u8) -> Option<Self> {
+        (n <= 9).then(|| Font::from_u8(n + 1))
+    }
 }
 
 impl FromU8 for Font {
@@ -12,19 +12,29 @@ pub enum FontStyle {
 }
 
 impl FontStyle {
-    #[must_use]
+    /// Italics and fraktur are separate attributes on some terminals, so both are unset
+    /// before `switched` from one to the other.
     #[inline]
-    pub const fn as_str(&self) -> &str {
+    pub(in crate::style) const fn as_str2(&self, switched: bool) -> &str {
         use FontStyle::{Fraktur, Italics, None, Unset};
 
-        match self {
-            None => "",
-            Italics => "\x1b[3m",
-            Fraktur => "\x1b[20m",
-            Unset => "\x1b[23m",
+        match (switched, self) {
+            (true, Italics) => "\x1b[23;3m",
+            (true, Fraktur) => "\x1b[23;20m",
+
+            (_, None) => "",
+            (_, Italics) => "\x1b[3m",
+            (_, Fraktur) => "\x1b[20m",
+            (_, Unset) => "\x1b[23m",
         }
     }
 
+    #[must_use]
+    #[inline]
+    pub const fn as_str(&self) -> &str {
+        self.as_str2(false)
+    }
+
     #[must_use]
     #[inline]
     pub const fn as_bytes(&self) -> &[u8] {
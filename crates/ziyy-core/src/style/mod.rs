@@ -102,10 +102,15 @@ impl Style {
     }
 
     define! {
-        pub fn set_font_style(0, 5, MAX_THREE_BITS);
+        pub fn set_font_style(0, 5, MAX_TWO_BITS);
         pub fn font_style() -> FontStyle;
     }
 
+    define! {
+        pub(crate) fn set_font_style_switched(0, 7, MAX_ONE_BIT);
+        pub(crate) fn font_style_switched() -> bool;
+    }
+
     /* self.0[1] */
     define! {
         pub fn set_invert(1, 0, MAX_TWO_BITS);
@@ -118,10 +123,15 @@ impl Style {
     }
 
     define! {
-        pub fn set_blink(1, 5, MAX_THREE_BITS);
+        pub fn set_blink(1, 5, MAX_TWO_BITS);
         pub fn blink() -> Blink;
     }
 
+    define! {
+        pub(crate) fn set_blink_switched(1, 7, MAX_ONE_BIT);
+        pub(crate) fn blink_switched() -> bool;
+    }
+
     /* self.0[2] */
     define! {
         pub fn set_reset(2, 3, MAX_ONE_BIT);
@@ -210,9 +220,9 @@ impl Style {
 
         let style = [
             cut(self.intensity().as_str2(self.prev_intensity())),
-            cut(self.font_style().as_str()),
+            cut(self.font_style().as_str2(self.font_style_switched())),
             cut(self.underline().as_str()),
-            cut(self.blink().as_str()),
+            cut(self.blink().as_str2(self.blink_switched())),
             cut(self.invert().as_str()),
             cut(self.hide().as_str()),
             cut(self.delete().as_str()),
@@ -259,6 +269,8 @@ impl Add for Style {
             intensity @ (Intensity::None | Intensity::Bold) => intensity,
             _ => Intensity::None,
         });
+        self.set_font_style_switched(false);
+        self.set_blink_switched(false);
 
         self.set_reset(self.reset() + rhs.reset());
         self.set_intensity(self.intensity() + rhs.intensity());
@@ -293,9 +305,17 @@ impl Sub for Style {
     #[inline]
     fn sub(mut self, rhs: Self) -> Self::Output {
         self.set_prev_intensity(match rhs.intensity() {
-            intensity @ (Intensity::None | Intensity::Bold) => intensity,
+            intensity @ (Intensity::None | Intensity::Bold | Intensity::Dim) => intensity,
             _ => Intensity::None,
         });
+        self.set_font_style_switched(matches!(
+            (self.font_style(), rhs.font_style()),
+            (FontStyle::Italics, FontStyle::Fraktur) | (FontStyle::Fraktur, FontStyle::Italics)
+        ));
+        self.set_blink_switched(matches!(
+            (self.blink(), rhs.blink()),
+            (Blink::Slow, Blink::Fast) | (Blink::Fast, Blink::Slow)
+        ));
 
         self.set_reset(self.reset() - rhs.reset());
         self.set_intensity(self.intensity() - rhs.intensity());
@@ -330,6 +350,8 @@ impl Not for Style {
     #[inline]
     fn not(mut self) -> Self::Output {
         self.set_prev_intensity(Intensity::None);
+        self.set_font_style_switched(false);
+        self.set_blink_switched(false);
 
         self.set_reset(!self.reset());
         self.set_intensity(!self.intensity());
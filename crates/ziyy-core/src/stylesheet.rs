@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::builtins::is_builtin_tag;
+use crate::context::Context;
+use crate::error::{Error, ErrorKind, Result};
+use crate::renderer::Renderer;
+use crate::shared::Span;
+use crate::style::Style;
+
+/// Custom tags shared by every render of a [`Renderer`].
+///
+/// A stylesheet is built from code with [`Stylesheet::define`] or parsed from `<let/>`
+/// declarations with [`Stylesheet::parse`], then attached to a renderer with
+/// [`Renderer::with_stylesheet`]. Bindings declared by a `<let/>` in the input take
+/// precedence over the stylesheet.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> ziyy_core::Result<'static, str, ()> {
+/// # use ziyy_core as ziyy;
+/// use ziyy::{Renderer, Stylesheet};
+///
+/// let sheet = Stylesheet::parse("<let id='error' c='red' b/>")?;
+/// let out = Renderer::new(String::new())
+///     .with_stylesheet(sheet)
+///     .render("<error>error:</error> file not found")?;
+/// assert_eq!(out, "\x1b[1;31merror:\x1b[22;39m file not found");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Stylesheet {
+    styles: HashMap<Vec<u8>, Style>,
+}
+
+impl Stylesheet {
+    /// Creates an empty stylesheet.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the `<let/>` declarations of `source`, like a `.zy` file. Anything else in
+    /// `source` is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `source` is malformed.
+    pub fn parse(source: &str) -> Result<'_, str, Self> {
+        let mut ctx = Context::new(source, None);
+        Renderer::new(String::new()).write_context(&mut ctx, None, None)?;
+
+        let styles = ctx
+            .bindings
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, style)| (name.to_vec(), style))
+            .collect();
+        Ok(Self { styles })
+    }
+
+    /// Binds `style` to the tag `name`, replacing the style it was bound to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is the name of a builtin tag, which `<let/>` can not
+    /// declare either.
+    pub fn define<'a>(&mut self, name: &'a str, style: Style) -> Result<'a, str, &mut Self> {
+        if is_builtin_tag(name) {
+            return Err(Error {
+                kind: ErrorKind::BuiltinTagOverwrite(name),
+                span: Span::inserted(),
+            });
+        }
+
+        self.styles.insert(name.as_bytes().to_vec(), style);
+        Ok(self)
+    }
+
+    /// Returns the style bound to the tag `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Style> {
+        self.binding(name.as_bytes())
+    }
+
+    pub(crate) fn binding(&self, name: &[u8]) -> Option<&Style> {
+        self.styles.get(name)
+    }
+}
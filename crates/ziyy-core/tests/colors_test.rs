@@ -155,3 +155,24 @@ pub fn it_recognizes_fixed_colors() {
         }
     }
 }
+
+#[test]
+pub fn it_recognizes_underline_colors() {
+    let test_cases = [
+        ("<u ul='red'>", Color::AnsiColor(AnsiColor::Red)),
+        ("<div ul='#fff'>", Color::Rgb(Rgb(255, 255, 255))),
+        ("<div ul='rgb(150, 75, 0)'>", Color::Rgb(Rgb(150, 75, 0))),
+        ("<span ul='fixed(225)'>", Color::Ansi256(Ansi256(225))),
+    ];
+
+    for (case, color) in test_cases {
+        let doc = render_to_tree(case);
+        let node = doc.root().first_child().unwrap();
+        let Chunk::Tag(tag) = node.value() else {
+            panic!()
+        };
+        let other = tag.style.ul_color();
+
+        assert!(other.eq(&color), "expected {color:?} and got {other:?}");
+    }
+}
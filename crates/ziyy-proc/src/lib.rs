@@ -1,17 +1,27 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::SystemTime;
+
 use proc_macro::TokenStream;
-use quote::quote_spanned;
-use syn::{LitStr, parse_macro_input};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::{LitStr, Token, bracketed, parse_macro_input};
+use ziyy_core::renderer::Format;
+use ziyy_core::{Position, Renderer, Stylesheet};
 
 /// A procedural macro that processes a string literal to apply custom styling.
 ///
 /// This macro takes a string literal as input and processes it using the `ziyy_core::try_style`
 /// function. It's designed to handle styled text transformations at compile time.
 ///
-/// # Panics
+/// Malformed markup is a compile error pointing at the error in the string literal, where
+/// the toolchain supports spans inside literals, or at the whole literal otherwise.
 ///
-/// Panics if:
-/// * The input is not a valid string literal
-/// * The `try_style` function returns an error during string processing
+/// The bindings declared in the stylesheet of the crate, if it has one, can be used in the
+/// literal, see [`stylesheet!`].
 ///
 /// # Example
 ///
@@ -23,10 +33,188 @@ use syn::{LitStr, parse_macro_input};
 #[proc_macro]
 pub fn zstr(tokens: TokenStream) -> TokenStream {
     let source = parse_macro_input!(tokens as LitStr);
+    expand(&source, Renderer::new(String::new()))
+}
+
+/// Calls a formatting macro of `std`, like `format!` or `write!`, with a rendered literal.
+///
+/// Used by the macros of `ziyy`, which call it as `zstr_format!(path, styled, [dst] "...",
+/// args...)`, with `plain` instead of `styled` to drop the styles and an empty `[]` for
+/// macros without a destination. The literal is given to the macro as is, so arguments
+/// keep their meaning, including arguments captured from the scope.
+#[doc(hidden)]
+#[proc_macro]
+pub fn zstr_format(tokens: TokenStream) -> TokenStream {
+    let FormatInput {
+        path,
+        styled,
+        dst,
+        source,
+        args,
+    } = parse_macro_input!(tokens as FormatInput);
+    let renderer = Renderer::new(String::new());
+    let renderer = if styled {
+        renderer
+    } else {
+        renderer.with_format(Format::Plain)
+    };
+
+    match render(&source, renderer) {
+        Ok(parsed) => {
+            let literal = LitStr::new(&parsed, source.span());
+            let dst = dst.map(|dst| quote! { #dst, });
+            TokenStream::from(quote! { #path!(#dst #literal #args) })
+        }
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// The input of [`zstr_format!`].
+struct FormatInput {
+    path: syn::Path,
+    styled: bool,
+    dst: Option<syn::Expr>,
+    source: LitStr,
+    args: proc_macro2::TokenStream,
+}
+
+impl Parse for FormatInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let mode: syn::Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let dst;
+        bracketed!(dst in input);
+
+        Ok(FormatInput {
+            path,
+            styled: mode != "plain",
+            dst: (!dst.is_empty()).then(|| dst.parse()).transpose()?,
+            source: input.parse()?,
+            args: input.parse()?,
+        })
+    }
+}
+
+/// Declares that the crate has a stylesheet.
+///
+/// The stylesheet is a file of `<let/>` declarations at the path in the `ZIYY_STYLESHEET`
+/// environment variable, relative to the root of the crate. Crates without the variable
+/// have no stylesheet. Its bindings can be used in every [`zstr!`] of the crate, and the
+/// printing macros of `ziyy`, without being declared again:
+///
+/// ```text
+/// <let id='error' c='red' b/>
+/// <let id='path' c='cyan' u/>
+/// ```
+///
+/// The variable can be set for the crate in `.cargo/config.toml`:
+///
+/// ```toml
+/// [env]
+/// ZIYY_STYLESHEET = { value = "ziyy.zy", relative = true }
+/// ```
+///
+/// `zstr!` reads the stylesheet whether or not it is declared, but the crate is only
+/// compiled again when the stylesheet or the variable changes if it is declared. The
+/// stylesheet is checked for errors here.
+///
+/// # Example
+///
+/// ```ignore
+/// ziyy::stylesheet!();
+///
+/// fn main() {
+///     ziyy::zprintln!("<error>error:</error> can not open <path>Cargo.toml</path>");
+/// }
+/// ```
+#[proc_macro]
+pub fn stylesheet(tokens: TokenStream) -> TokenStream {
+    let span = proc_macro2::Span::call_site();
+    if let Some(token) = proc_macro2::TokenStream::from(tokens).into_iter().next() {
+        return syn::Error::new(
+            token.span(),
+            "unexpected token, `stylesheet!` takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let path = match load_stylesheet() {
+        Ok(Some((path, _))) => path,
+        Ok(None) => {
+            let message = "no stylesheet, set `ZIYY_STYLESHEET` to the path of the stylesheet";
+            return syn::Error::new(span, message).to_compile_error().into();
+        }
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let path = path.to_string_lossy();
+    // the crate is compiled again when a file it includes or a variable it reads changes
+    TokenStream::from(quote! {
+        const _: &[u8] = ::core::include_bytes!(#path);
+        const _: ::core::option::Option<&str> = ::core::option_env!("ZIYY_STYLESHEET");
+    })
+}
+
+/// Stylesheets parsed in this compilation by path, with the time their file was modified.
+///
+/// The macros of a compilation share them, so a stylesheet is parsed once and not for every
+/// `zstr!`. Hosts that expand macros for longer, like editors, parse it again when it
+/// changes.
+static STYLESHEETS: LazyLock<Mutex<HashMap<PathBuf, (SystemTime, Stylesheet)>>> =
+    LazyLock::new(Mutex::default);
+
+/// Returns the path of the stylesheet of the crate being compiled, `None` if it has none.
+fn stylesheet_path() -> Option<PathBuf> {
+    let path = env::var_os("ZIYY_STYLESHEET")?;
+    let root = env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    Some(PathBuf::from(root).join(path))
+}
+
+/// Returns the stylesheet of the crate being compiled, `None` if it has none.
+fn load_stylesheet() -> syn::Result<Option<(PathBuf, Stylesheet)>> {
+    let Some(path) = stylesheet_path() else {
+        return Ok(None);
+    };
+    let error = |error| {
+        let message = format!("can not read stylesheet `{}`: {error}", path.display());
+        syn::Error::new(proc_macro2::Span::call_site(), message)
+    };
+
+    let modified = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(error)?;
+    let mut stylesheets = STYLESHEETS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((time, sheet)) = stylesheets.get(&path)
+        && *time == modified
+    {
+        return Ok(Some((path, sheet.clone())));
+    }
+
+    let sheet = parse_stylesheet(&path, &fs::read_to_string(&path).map_err(error)?)?;
+    stylesheets.insert(path.clone(), (modified, sheet.clone()));
+    Ok(Some((path, sheet)))
+}
+
+/// Parses a stylesheet. Errors are given with their location in the file.
+fn parse_stylesheet(path: &Path, sheet: &str) -> syn::Result<Stylesheet> {
+    match Stylesheet::parse(sheet) {
+        Ok(stylesheet) => Ok(stylesheet),
+        Err(e) => Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("{} at {}{}", e.kind(), path.display(), e.span()),
+        )),
+    }
+}
+
+/// Renders a string literal, or emits a `compile_error!` spanning the malformed markup.
+fn expand(source: &LitStr, renderer: Renderer<String>) -> TokenStream {
     let span = source.span();
-    let parsed = match ziyy_core::try_style(&source.value()) {
+    let parsed = match render(source, renderer) {
         Ok(s) => s,
-        Err(e) => panic!("{e}"),
+        Err(error) => return error.to_compile_error().into(),
     };
 
     let expanded = quote_spanned! {
@@ -35,3 +223,159 @@ pub fn zstr(tokens: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Renders a string literal. Errors span the malformed markup.
+fn render(source: &LitStr, renderer: Renderer<String>) -> syn::Result<String> {
+    let span = source.span();
+    let value = source.value();
+    let renderer = match load_stylesheet()? {
+        Some((_, sheet)) => renderer.with_stylesheet(sheet),
+        None => renderer,
+    };
+    let hidden = hide_placeholders(&value);
+    let parsed = match renderer.render(hidden.as_deref().unwrap_or(&value)) {
+        Ok(s) if hidden.is_some() => s.replace(LT, "<").replace(GT, ">"),
+        Ok(s) => s,
+        Err(e) => {
+            let error = e.span();
+            let subspan = (error.start.row > 0)
+                .then(|| {
+                    let token = source.token();
+                    let literal = token.to_string();
+                    let start = source_offset(&literal, value_offset(&value, error.start))?;
+                    let end = source_offset(&literal, value_offset(&value, error.end))?;
+                    token.subspan(start..end.max(start + 1))
+                })
+                .flatten();
+            let message = match subspan {
+                Some(_) => e.kind().to_string(),
+                // the location is only given in the message
+                None => format!("{} at {error}", e.kind()),
+            };
+            return Err(syn::Error::new(subspan.unwrap_or(span), message));
+        }
+    };
+
+    Ok(parsed)
+}
+
+/// Bytes standing for `<` and `>` in the placeholders of a format string.
+const LT: char = '\x01';
+const GT: char = '\x02';
+
+/// Hides the `<` and `>` of the placeholders of a format string, like `{:>6}`, which would
+/// be read as tags. They are replaced with bytes of the same length, so errors keep their
+/// location. Returns `None` if there is nothing to hide or the bytes are already used.
+fn hide_placeholders(value: &str) -> Option<String> {
+    if value.contains([LT, GT]) {
+        return None;
+    }
+
+    let mut hidden = String::with_capacity(value.len());
+    let mut placeholder = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if !placeholder && chars.peek() == Some(&c) => {
+                chars.next();
+                hidden.extend([c, c]);
+                continue;
+            }
+            '{' => placeholder = true,
+            '}' => placeholder = false,
+            '<' if placeholder => {
+                hidden.push(LT);
+                continue;
+            }
+            '>' if placeholder => {
+                hidden.push(GT);
+                continue;
+            }
+            _ => {}
+        }
+        hidden.push(c);
+    }
+    (hidden != value).then_some(hidden)
+}
+
+/// Returns the byte offset in `value` of a position, with rows and columns starting at 1.
+fn value_offset(value: &str, pos: Position) -> usize {
+    let row: usize = value
+        .split_inclusive('\n')
+        .take(pos.row as usize - 1)
+        .map(str::len)
+        .sum();
+    row + pos.col as usize - 1
+}
+
+/// Returns the byte offset in the source of a string literal of the byte at `offset` in its
+/// value, skipping the quotes and escape sequences.
+fn source_offset(literal: &str, offset: usize) -> Option<usize> {
+    // raw strings have no escape sequences
+    if let Some(rest) = literal.strip_prefix('r') {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        return Some(hashes + 2 + offset);
+    }
+
+    let bytes = literal.as_bytes();
+    // `i` is after the opening quote
+    let (mut i, mut n) = (1, 0);
+    loop {
+        // a line continuation skips the newline and the leading whitespace
+        if bytes.get(i) == Some(&b'\\') && matches!(bytes.get(i + 1), Some(b'\n' | b'\r')) {
+            i += 2;
+            while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+                i += 1;
+            }
+            continue;
+        }
+        if n >= offset {
+            return Some(i);
+        }
+        if bytes.get(i)? != &b'\\' {
+            i += 1;
+            n += 1;
+            continue;
+        }
+        match bytes.get(i + 1)? {
+            b'x' => {
+                i += 4;
+                n += 1;
+            }
+            b'u' => {
+                let close = i + literal[i..].find('}')?;
+                let digits = literal[i + 3..close].replace('_', "");
+                let c = char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?;
+                i = close + 1;
+                n += c.len_utf8();
+            }
+            _ => {
+                i += 2;
+                n += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hide_placeholders, source_offset};
+
+    #[test]
+    fn hide_placeholders_keeps_tags() {
+        assert_eq!(hide_placeholders("<b>{}</b>"), None);
+        assert_eq!(
+            hide_placeholders("<b>{x:>6}</b>{{<i>}}{:<2}").as_deref(),
+            Some("<b>{x:\x026}</b>{{<i>}}{:\x012}")
+        );
+        assert_eq!(hide_placeholders("\x01{:>6}"), None);
+    }
+
+    #[test]
+    fn source_offset_skips_escapes() {
+        assert_eq!(source_offset(r#""abc""#, 2), Some(3));
+        assert_eq!(source_offset(r##"r#"abc"#"##, 2), Some(5));
+        assert_eq!(source_offset(r#""\t\x41\u{e9}b""#, 5), Some(14));
+        assert_eq!(source_offset("\"a\\\n   b\"", 1), Some(7));
+    }
+}
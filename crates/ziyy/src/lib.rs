@@ -1,4 +1,6 @@
 pub use ziyy_core::*;
-pub use ziyy_proc::zstr;
+#[doc(hidden)]
+pub use ziyy_proc::zstr_format as __zstr_format;
+pub use ziyy_proc::{stylesheet, zstr};
 
 mod macros;
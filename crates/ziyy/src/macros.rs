@@ -70,6 +70,8 @@ macro_rules! zformat {
 /// Use `zprint!` only for the primary output of your program. Use
 /// [`zeprint!`] instead to print error and progress messages.
 ///
+/// Styles are dropped when stdout is not a terminal, following [`Policy::Auto`].
+///
 /// See the formatting documentation in [`std::fmt`](crate::fmt)
 /// for details of the macro argument syntax.
 ///
@@ -77,6 +79,7 @@ macro_rules! zformat {
 /// [`zprintln!`]: crate::zprintln
 /// [`zeprint!`]: crate::zeprint
 /// [lock]: std::io::Stdout
+/// [`Policy::Auto`]: crate::renderer::Policy::Auto
 ///
 /// # Panics
 ///
@@ -132,6 +135,8 @@ macro_rules! zprint {
 /// Use `zprintln!` only for the primary output of your program. Use
 /// [`zeprintln!`] instead to print error and progress messages.
 ///
+/// Like [`zprint!`], styles are dropped when stdout is not a terminal.
+///
 /// See the formatting documentation in [`std::fmt`](std::fmt)
 /// for details of the macro argument syntax.
 ///
@@ -177,6 +182,8 @@ macro_rules! zprintln {
 /// Use `eprint!` only for error and progress messages. Use `zprint!`
 /// instead for the primary output of your program.
 ///
+/// Styles are dropped when stderr is not a terminal.
+///
 /// [`io::stderr`]: std::io::stderr
 /// [`io::stdout`]: std::io::stdout
 ///
@@ -211,6 +218,8 @@ macro_rules! zeprint {
 /// Use `eprintln!` only for error and progress messages. Use `println!`
 /// instead for the primary output of your program.
 ///
+/// Like [`zeprint!`], styles are dropped when stderr is not a terminal.
+///
 /// See the formatting documentation in [`std::fmt`](crate::fmt)
 /// for details of the macro argument syntax.
 ///
@@ -372,26 +381,37 @@ macro_rules! zwriteln {
 
 #[doc(hidden)]
 #[macro_export]
-#[cfg(not(feature = "terminfo"))]
 macro_rules! __style {
-    ($name:tt $s:tt $($arg:tt)*) => {
-        ::std::$name!($crate::zstr!($s) $($arg)*)
+    (format $($arg:tt)*) => {
+        $crate::__zstr_format!(::std::format, styled, [] $($arg)*)
     };
 
-    ($name:tt $dst:expr, $s:tt $($arg:tt)*) => {
-        ::std::$name!($dst, $crate::zstr!($s) $($arg)*)
+    (print $($arg:tt)*) => {
+        $crate::__style!(@auto stdout print $($arg)*)
     };
-}
 
-#[doc(hidden)]
-#[macro_export]
-#[cfg(feature = "terminfo")]
-macro_rules! __style {
-    ($name:tt $($arg:tt)*) => {
-        ::std::$name!("{}", $crate::style(::std::format!($($arg)*)))
+    (println $($arg:tt)*) => {
+        $crate::__style!(@auto stdout println $($arg)*)
+    };
+
+    (eprint $($arg:tt)*) => {
+        $crate::__style!(@auto stderr eprint $($arg)*)
+    };
+
+    (eprintln $($arg:tt)*) => {
+        $crate::__style!(@auto stderr eprintln $($arg)*)
+    };
+
+    // the literal is rendered when compiling, so arguments are never parsed as markup
+    (@auto $stream:ident $name:tt $($arg:tt)*) => {
+        if $crate::renderer::Policy::Auto.should_style(&::std::io::$stream()) {
+            $crate::__zstr_format!(::std::$name, styled, [] $($arg)*)
+        } else {
+            $crate::__zstr_format!(::std::$name, plain, [] $($arg)*)
+        }
     };
 
     ($name:tt $dst:expr, $($arg:tt)*) => {
-        ::std::$name!($dst, "{}", $crate::style(::std::format!($($arg)*)))
+        $crate::__zstr_format!(::std::$name, styled, [$dst] $($arg)*)
     };
 }
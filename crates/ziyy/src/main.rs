@@ -1,10 +1,12 @@
 use getopts::{Matches, Options, ParsingStyle};
 use std::env;
 use std::fs::File;
-use std::io::{BufReader, Read, Write, stdin, stdout};
+use std::io::{BufReader, IsTerminal, Read, Write, stderr, stdin, stdout};
 use std::path::Path;
 use std::process::exit;
-use ziyy::{Error, Renderer, zprint};
+use ziyy::renderer::{Format, Policy, terminal_width};
+use ziyy::style::ColorDepth;
+use ziyy::{Error, Renderer, ansi_to_markup, zprint};
 #[cfg(feature = "tree")]
 use ziyy_core::render_to_tree;
 
@@ -21,6 +23,11 @@ fn main() {
     opts.optflag("e", "ansi", "");
     opts.optflag("n", "no-newline", "");
     opts.optflag("", "strip", "");
+    opts.optflag("", "html", "");
+    opts.optflag("", "from-ansi", "");
+    opts.optopt("", "color", "", "WHEN");
+    opts.optopt("", "color-depth", "", "DEPTH");
+    opts.optopt("", "width", "", "COLS");
     opts.optflag("", "tree", "");
     opts.optflag("h", "help", "");
     opts.optflag("V", "version", "");
@@ -80,45 +87,98 @@ fn main() {
 }
 
 fn parse_to_out(source: &str, out: &mut impl Write, matches: &Matches) {
+    let policy = match matches.opt_str("color").as_deref() {
+        Some("always") => Policy::Always,
+        Some("auto") | None => Policy::Auto,
+        Some("never") => Policy::Never,
+        Some(when) => invalid_value("color", when, "always, auto or never"),
+    };
+
     let mut f = || {
         #[cfg(feature = "tree")]
         if matches.opt_present("tree") {
-            let _ = out.write(render_to_tree(source).to_string().as_bytes());
-            return Ok(());
+            return match out.write_all(render_to_tree(source).to_string().as_bytes()) {
+                Ok(()) => Vec::new(),
+                Err(err) => vec![err.into()],
+            };
         }
 
-        match matches.opt_present("e") {
-            true => todo!(),
-            false => parse(&source, out),
-        }?;
-
-        /* if options.strip {
-            output.root().strip_styles();
-        } */
+        if matches.opt_present("from-ansi") {
+            return match out.write_all(ansi_to_markup(source).as_bytes()) {
+                Ok(()) => Vec::new(),
+                Err(err) => vec![err.into()],
+            };
+        }
 
-        /* let mut buf = String::new();
-        if options.tree {
-            buf = output.to_string();
+        let format = if matches.opt_present("strip") {
+            Format::Plain
+        } else if matches.opt_present("html") {
+            Format::Html
         } else {
-            output.root().to_string(&mut buf);
-        } */
-
-        // let _ = out.write(buf.as_bytes());
-        Ok::<(), Error<str>>(())
+            Format::Ansi
+        };
+
+        let depth = match matches.opt_str("color-depth").as_deref() {
+            Some("truecolor") => ColorDepth::TrueColor,
+            Some("256") => ColorDepth::Ansi256,
+            Some("16") => ColorDepth::Ansi16,
+            Some("none") => ColorDepth::None,
+            Some(depth) => invalid_value("color-depth", depth, "truecolor, 256, 16 or none"),
+            None => ColorDepth::detect(),
+        };
+
+        let width = match matches.opt_str("width").as_deref() {
+            Some("auto") => terminal_width(),
+            Some(cols) => match cols.parse() {
+                Ok(cols) => Some(cols),
+                Err(_) => invalid_value("width", cols, "a number of columns or auto"),
+            },
+            None => None,
+        };
+
+        parse(
+            source,
+            out,
+            format,
+            depth,
+            policy,
+            width,
+            matches.opt_present("e"),
+        )
     };
-    if let Err(err) = f() {
-        println!(
-            "{}",
-            err.to_string()
-                .replace("at :", &format!("\x1b[1;34mat\x1b[22;39m :"))
-        );
+
+    let errors = f();
+    if !errors.is_empty() {
+        let styled = policy.should_style(&stderr());
+        for err in &errors {
+            eprint!("{}", err.diagnostic(source).styled(styled));
+        }
         exit(1)
     }
 }
 
-pub fn parse<'src>(source: &'src str, out: &mut impl Write) -> ziyy::Result<'src, str, ()> {
-    let mut renderer = Renderer::new(out);
-    renderer.write_str(source)
+fn invalid_value(option: &str, value: &str, expected: &str) -> ! {
+    eprintln!("Invalid value '{value}' for option '--{option}': expected {expected}");
+    exit(1)
+}
+
+pub fn parse<'src>(
+    source: &'src str,
+    out: &mut impl Write,
+    format: Format,
+    depth: ColorDepth,
+    policy: Policy,
+    width: Option<usize>,
+    escapes_only: bool,
+) -> Vec<Error<'src, str>> {
+    let mut renderer = Renderer::new(out)
+        .with_format(format)
+        .with_color_depth(depth)
+        .with_policy(policy)
+        .with_terminal(stdout().is_terminal())
+        .with_width(width)
+        .with_escapes_only(escapes_only);
+    renderer.write_str_recovering(source)
 }
 
 fn print_usage() {
@@ -134,13 +194,18 @@ fn print_usage() {
 <g>Usage:</g> <cy><b>{0}</b> [OPTIONS] \<FILE\>\n       <b>{0}</b> [OPTIONS] <b>-c</b> [ARGS]...</cy>
 
 <g>Options:</g>
-<bc>  -V, --version</bc>     Print version info and exit
-<bc>  -c, --cli</bc>         Read input from cli, defaults to stdin if no arguments
-<bc>  -e, --ansi</bc>        Parse escape sequences only
-<bc>  -n, --no-newline</bc>  Suppress emiting newline after output. Available only on --cli option
-<bc>  -h, --help</bc>        Print help
-<bc>      --strip</bc>       Strip styles from output
-<bc>      --tree</bc>        Strip styles from output
+<bc>  -V, --version</bc>              Print version info and exit
+<bc>  -c, --cli</bc>                  Read input from cli, defaults to stdin if no arguments
+<bc>  -e, --ansi</bc>                 Parse escape sequences only
+<bc>  -n, --no-newline</bc>           Suppress emiting newline after output. Available only on --cli option
+<bc>  -h, --help</bc>                 Print help
+<bc>      --strip</bc>                Strip styles from output
+<bc>      --html</bc>                 Render output as HTML
+<bc>      --from-ansi</bc>            Convert ANSI escape sequences in input to ziyy markup
+<bc>      --color</bc> \<WHEN\>         When to style output: always, auto or never. Defaults to auto
+<bc>      --color-depth</bc> \<DEPTH\>  Color depth: truecolor, 256, 16 or none. Detected by default
+<bc>      --width</bc> \<COLS\>         Wrap lines at COLS columns, or at the terminal width if auto
+<bc>      --tree</bc>                 Strip styles from output
 </pre>
         </ziyy>"#,
         env!("CARGO_BIN_NAME")
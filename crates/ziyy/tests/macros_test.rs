@@ -0,0 +1,18 @@
+use std::fmt::Write;
+
+use ziyy::{zformat, zwrite, zwriteln};
+
+#[test]
+pub fn it_formats_arguments_like_format() {
+    let name = "<i>";
+    let styled = zformat!("<b>{0}</b> has {n} items, {:?} {name:>6}|", "a", n = 2);
+    assert_eq!(styled, "\x1b[1ma\x1b[22m has 2 items, \"a\"    <i>|");
+}
+
+#[test]
+pub fn it_writes_arguments_like_write() {
+    let mut s = String::new();
+    zwrite!(s, "<i>{:03}</i>", 7).unwrap();
+    zwriteln!(s, " {x:.1}", x = 1.25).unwrap();
+    assert_eq!(s, "\x1b[3m007\x1b[23m 1.2\n");
+}